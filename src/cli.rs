@@ -1,110 +1,7 @@
 use std::str::FromStr;
 
 use clap::{crate_authors, crate_description, crate_version, ArgEnum, Parser};
-use strum::EnumIter;
-
-// TODO: rot13 & rotN
-// TODO: binary, octal
-// TODO: utf16le, ucs2, ucs-2, latin1
-#[derive(Debug, ArgEnum, EnumIter, Clone, PartialEq, Eq)]
-pub enum Encoding {
-    Raw,
-    // Character encodings
-    ASCII,
-    UTF8,
-    // Digit encodings
-    Hex,
-    // Base32
-    #[clap(name("base32"))]
-    Base32Rfc4648,
-    #[clap(name("base32|"))]
-    Base32Rfc4648NoPadding,
-    #[clap(name("base32:crockford"))]
-    Base32Crockford,
-    // Base64
-    #[clap(name("base64"))]
-    Base64Standard,
-    #[clap(name("base64|"))]
-    Base64StandardNoPadding,
-    #[clap(name("base64:url"))]
-    Base64UrlSafe,
-    #[clap(name("base64:url|"))]
-    Base64UrlSafeNoPadding,
-    #[clap(name("base64:bcrypt"))]
-    Base64Bcrypt,
-    #[clap(name("base64:binhex"))]
-    Base64Binhex,
-    #[clap(name("base64:crypt"))]
-    Base64Crypt,
-    #[clap(name("base64:imap"))]
-    Base64ImapMutf7,
-    // Base85
-    #[clap(name("base85"))]
-    Base85Rfc1924,
-    #[clap(name("ascii85"))]
-    Base85Ascii,
-}
-
-impl ToString for Encoding {
-    fn to_string(&self) -> String {
-        match self {
-            Self::Raw => "raw".into(),
-            Self::ASCII => "ascii".into(),
-            Self::UTF8 => "utf8".into(),
-            Self::Hex => "hex".into(),
-
-            Self::Base32Crockford => "base32:crockford".into(),
-            Self::Base32Rfc4648 => "base32".into(),
-            Self::Base32Rfc4648NoPadding => "base32|".into(),
-
-            Self::Base64Bcrypt => "base64:bcrypt".into(),
-            Self::Base64Binhex => "base64:binhex".into(),
-            Self::Base64Crypt => "base64:crypt".into(),
-            Self::Base64ImapMutf7 => "base64:imap".into(),
-            Self::Base64Standard => "base64".into(),
-            Self::Base64StandardNoPadding => "base64|".into(),
-            Self::Base64UrlSafe => "base64:url".into(),
-            Self::Base64UrlSafeNoPadding => "base64:url|".into(),
-
-            Self::Base85Rfc1924 => "base85".into(),
-            Self::Base85Ascii => "ascii85".into(),
-        }
-    }
-}
-
-impl FromStr for Encoding {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "raw" => Ok(Self::Raw),
-            "ascii" => Ok(Self::ASCII),
-            "utf8" => Ok(Self::UTF8),
-            "hex" => Ok(Self::Hex),
-
-            "base32" => Ok(Self::Base32Rfc4648),
-            "base32|" => Ok(Self::Base32Rfc4648NoPadding),
-            "base32:crockford" => Ok(Self::Base32Crockford),
-            "base32:rfc4648" => Ok(Self::Base32Rfc4648),
-            "base32:rfc4648|" => Ok(Self::Base32Rfc4648NoPadding),
-
-            "base64" => Ok(Self::Base64Standard),
-            "base64|" => Ok(Self::Base64StandardNoPadding),
-            "base64:bcrypt" => Ok(Self::Base64Bcrypt),
-            "base64:binhex" => Ok(Self::Base64Binhex),
-            "base64:crypt" => Ok(Self::Base64Crypt),
-            "base64:imap" => Ok(Self::Base64ImapMutf7),
-            "base64:standard" => Ok(Self::Base64Standard),
-            "base64:standard|" => Ok(Self::Base64StandardNoPadding),
-            "base64:url" => Ok(Self::Base64UrlSafe),
-            "base64:url|" => Ok(Self::Base64UrlSafeNoPadding),
-
-            "base85" => Ok(Self::Base85Rfc1924),
-            "ascii85" => Ok(Self::Base85Ascii),
-
-            _ => Err(format!("Unknown encoding: {}", s)),
-        }
-    }
-}
+use trrs::Encoding;
 
 #[derive(Debug, Clone, ArgEnum)]
 pub enum OutputFormat {
@@ -126,27 +23,43 @@ impl FromStr for OutputFormat {
 #[derive(Debug, Parser)]
 #[clap(version = crate_version!(), author = crate_authors!(), after_help = crate_description!())]
 pub struct Args {
-    /// The input and output encoding. Encodings that end with `|` do not have padding.
+    /// The input and output encoding, see `--list` for all of them. Encodings that end with `|` do
+    /// not have padding, and some take an argument after a `:`, e.g. `rot:3`.
+    ///
+    /// Given more than two, the input is decoded from each encoding in turn and then encoded as the
+    /// last one, e.g. `hex base64 ascii85` turns hex encoded base64 into ascii85.
+    #[clap(name = "type", min_values = 2, multiple_occurrences = false)]
+    pub encoding: Vec<Encoding>,
+
+    /// An encoding in the chain, can be repeated instead of giving the type arg (see type arg)
     #[clap(
-        arg_enum,
-        name = "type",
-        number_of_values = 2,
-        multiple_occurrences = false
+        short = 'T',
+        long = "type",
+        multiple_occurrences = true,
+        number_of_values = 1,
+        conflicts_with = "type"
     )]
-    pub encoding: Vec<Encoding>,
+    pub chain: Vec<Encoding>,
 
-    /// The file to read from or `-` to read from STDIN
-    #[clap(short = 'i', long = "in", default_value = "-")]
-    pub input: String,
+    /// The file to read from or `-` to read from STDIN, can be repeated to read several files one
+    /// after the other, e.g. the parts of a multipart yEnc file. A line break is added between
+    /// files that don't end in one. Encodings that store a file name, like uu and yenc, use the
+    /// name of the first file unless it's STDIN
+    #[clap(
+        short = 'i',
+        long = "in",
+        default_value = "-",
+        multiple_occurrences = true,
+        number_of_values = 1
+    )]
+    pub input: Vec<String>,
 
     /// The encoding of the input (see type arg)
     #[clap(
-        arg_enum,
-        hide_possible_values(true),
         short = 'I',
         long = "in-type",
-        required_unless_present = "type",
-        conflicts_with = "type"
+        required_unless_present_any = &["type", "chain", "peel", "list"],
+        conflicts_with_all = &["type", "chain"]
     )]
     pub input_type: Option<Encoding>,
 
@@ -156,26 +69,58 @@ pub struct Args {
 
     /// The encoding of the output (see type arg)
     #[clap(
-        arg_enum,
-        hide_possible_values(true),
         short = 'O',
         long = "out-type",
-        required_unless_present = "type",
-        conflicts_with = "type"
+        required_unless_present_any = &["type", "chain", "peel", "list"],
+        conflicts_with_all = &["type", "chain"]
     )]
     pub output_type: Option<Encoding>,
 
     /// Controls how data is printed to STDOUT
     #[clap(arg_enum, short = 'F', long = "out-format")]
     pub output_format: Option<OutputFormat>,
+
+    /// Print the encodings the input could be in to STDERR, best match first (requires the `auto`
+    /// input type)
+    #[clap(long = "explain")]
+    pub explain: bool,
+
+    /// Detect and decode layers of encodings until the input no longer looks encoded, printing the
+    /// encodings that were found to STDERR (the output type defaults to raw)
+    #[clap(long = "peel", conflicts_with_all = &["type", "chain", "input-type"])]
+    pub peel: bool,
+
+    /// List every encoding with its description and exit
+    #[clap(
+        long = "list",
+        conflicts_with_all = &["type", "chain", "input-type", "output-type", "peel"]
+    )]
+    pub list: bool,
+}
+
+impl Args {
+    /// The encodings to transform the input through, from first to last.
+    ///
+    /// When peeling, this is only the output encoding.
+    pub fn chain(&self) -> Vec<Encoding> {
+        if self.peel {
+            let raw = "raw".parse().unwrap();
+            return vec![self.output_type.clone().unwrap_or(raw)];
+        }
+
+        match (&self.input_type, &self.output_type) {
+            (Some(input), Some(output)) => vec![input.clone(), output.clone()],
+            _ if !self.chain.is_empty() => self.chain.clone(),
+            _ => self.encoding.clone(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use clap::Parser;
 
     use crate::cli::Args;
-    use crate::cli::Encoding;
 
     #[test]
     fn clap() {
@@ -184,20 +129,44 @@ mod tests {
     }
 
     #[test]
-    fn serde() {
-        let t = |e: Encoding| assert_eq!(e, Encoding::from_str(&e.to_string()).unwrap());
-
-        // Test all default names
-        use strum::IntoEnumIterator;
-        for enc in Encoding::iter() {
-            t(enc);
-        }
-
-        // Test short names
-        let t = |e: Encoding, s| assert_eq!(e, Encoding::from_str(s).unwrap());
-        t(Encoding::Base32Rfc4648, "base32");
-        t(Encoding::Base32Rfc4648NoPadding, "base32|");
-        t(Encoding::Base64Standard, "base64");
-        t(Encoding::Base64StandardNoPadding, "base64|");
+    fn chain() {
+        let t = |args: &[&str], chain: &[&str]| {
+            let args = Args::try_parse_from([&["trrs"], args].concat()).unwrap();
+            let names = args
+                .chain()
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, chain);
+        };
+
+        t(&["hex", "base64"], &["hex", "base64"]);
+        t(&["caesar:3", "rot13"], &["rot:3", "rot13"]);
+        t(&["-I", "hex", "-O", "base64"], &["hex", "base64"]);
+        t(&["hex", "base64", "ascii85"], &["hex", "base64", "ascii85"]);
+        t(
+            &["-T", "hex", "-T", "base64", "-T", "raw"],
+            &["hex", "base64", "raw"],
+        );
+
+        assert!(Args::try_parse_from(["trrs", "hex"]).is_err());
+        assert!(Args::try_parse_from(["trrs", "-T", "hex", "base64", "raw"]).is_err());
+        assert!(Args::try_parse_from(["trrs", "-T", "hex", "-O", "raw"]).is_err());
+
+        t(&["--peel"], &["raw"]);
+        t(&["--peel", "-O", "hex"], &["hex"]);
+        assert!(Args::try_parse_from(["trrs", "--peel", "hex", "raw"]).is_err());
+        assert!(Args::try_parse_from(["trrs", "--peel", "-I", "hex"]).is_err());
+
+        assert!(Args::try_parse_from(["trrs", "--list"]).is_ok());
+        assert!(Args::try_parse_from(["trrs", "--list", "hex", "raw"]).is_err());
+        assert!(Args::try_parse_from(["trrs", "rot", "raw"]).is_err());
+
+        let args = Args::try_parse_from(["trrs", "-i", "a.txt", "-i", "b.txt", "yenc", "raw"]);
+        assert_eq!(args.unwrap().input, ["a.txt", "b.txt"]);
+        assert_eq!(
+            Args::try_parse_from(["trrs", "hex", "raw"]).unwrap().input,
+            ["-"]
+        );
     }
 }
@@ -0,0 +1,30 @@
+use super::Codec;
+use crate::detect::detect;
+use crate::{Error, Result};
+
+/// Decodes from whichever encoding the input looks like it's in.
+pub struct Auto;
+
+impl Codec for Auto {
+    fn name(&self) -> &'static str {
+        "auto"
+    }
+
+    fn description(&self) -> &'static str {
+        "detect the encoding of the input (can only be used for input)"
+    }
+
+    fn encode(&self, _: &[u8]) -> Result<Vec<u8>> {
+        Err(Error::encode(
+            self.name(),
+            "auto can only be used to decode",
+        ))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match detect(data) {
+            Some(candidate) => Ok(candidate.decoded),
+            None => Err(Error::decode(self.name(), "could not detect the encoding")),
+        }
+    }
+}
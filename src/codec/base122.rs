@@ -0,0 +1,114 @@
+use super::Codec;
+use crate::{Error, Result};
+
+/// Seven bit values that can't be written as a single byte, since they'd need escaping in HTML
+/// or JavaScript strings.
+const ILLEGAL: [u8; 6] = [0, b'\n', b'\r', b'"', b'&', b'\\'];
+
+/// Marks a two byte character that holds only the final seven bits, rather than an illegal value
+/// followed by seven more bits.
+const SHORTENED: u8 = 0b111;
+
+/// Kevin Albertson's Base122, which writes seven bits per byte of UTF-8.
+///
+/// Values that are illegal on their own are folded into a two byte character along with the
+/// next seven bits.
+pub struct Base122;
+
+/// Split `data` into seven bit values, padding the last with zeros.
+fn septets(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    let (mut queue, mut bits) = (0u32, 0);
+    for &b in data {
+        queue = queue << 8 | b as u32;
+        bits += 8;
+        while bits >= 7 {
+            bits -= 7;
+            out.push((queue >> bits) as u8 & 0x7f);
+        }
+    }
+
+    if bits > 0 {
+        out.push((queue << (7 - bits)) as u8 & 0x7f);
+    }
+
+    out
+}
+
+impl Codec for Base122 {
+    fn name(&self) -> &'static str {
+        "base122"
+    }
+
+    fn description(&self) -> &'static str {
+        "Base122, which outputs UTF-8 that needs no escaping in HTML"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let mut septets = septets(data).into_iter();
+        while let Some(value) = septets.next() {
+            let illegal = match ILLEGAL.iter().position(|i| *i == value) {
+                Some(illegal) => illegal as u8,
+                None => {
+                    out.push(value);
+                    continue;
+                }
+            };
+
+            let (index, next) = match septets.next() {
+                Some(next) => (illegal, next),
+                None => (SHORTENED, value),
+            };
+            out.push(0b1100_0010 | index << 2 | next >> 6);
+            out.push(0b1000_0000 | next & 0x3f);
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let (mut queue, mut bits) = (0u32, 0);
+        let mut push = |value: u8| {
+            queue = queue << 7 | value as u32;
+            bits += 7;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((queue >> bits) as u8);
+            }
+        };
+
+        let mut i = 0;
+        while i < data.len() {
+            match data[i..] {
+                [c, ..] if c < 0x80 => {
+                    push(c);
+                    i += 1;
+                }
+                [b1, b2, ..] if b1 & 0b1110_0010 == 0b1100_0010 && b2 & 0xc0 == 0x80 => {
+                    let index = (b1 >> 2) & 0b111;
+                    if index != SHORTENED {
+                        let illegal = *ILLEGAL.get(index as usize).ok_or_else(|| {
+                            Error::decode(
+                                self.name(),
+                                format!("unknown escape {} at offset {}", index, i),
+                            )
+                        })?;
+                        push(illegal);
+                    }
+                    push((b1 & 1) << 6 | b2 & 0x3f);
+                    i += 2;
+                }
+                _ => {
+                    return Err(Error::decode(
+                        self.name(),
+                        format!("invalid character at offset {}", i),
+                    ))
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
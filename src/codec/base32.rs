@@ -0,0 +1,225 @@
+use std::str;
+
+use base32::Alphabet;
+
+use super::Codec;
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+pub struct Base32 {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    alphabet: Chars,
+}
+
+/// The characters an encoding uses.
+enum Chars {
+    /// One of the alphabets that the base32 crate supports.
+    Crate(Alphabet),
+    /// Any other alphabet, padded with `=` if `padding` is set.
+    Custom {
+        alphabet: &'static [u8; 32],
+        padding: bool,
+    },
+}
+
+const HEX_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+const Z_ALPHABET: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+pub const RFC4648: Base32 = Base32 {
+    name: "base32",
+    aliases: &["base32:rfc4648"],
+    description: "RFC4648 base32",
+    alphabet: Chars::Crate(Alphabet::RFC4648 { padding: true }),
+};
+
+pub const RFC4648_NO_PADDING: Base32 = Base32 {
+    name: "base32|",
+    aliases: &["base32:rfc4648|"],
+    description: "RFC4648 base32 without padding",
+    alphabet: Chars::Crate(Alphabet::RFC4648 { padding: false }),
+};
+
+pub const CROCKFORD: Base32 = Base32 {
+    name: "base32:crockford",
+    aliases: &[],
+    description: "Crockford's base32",
+    alphabet: Chars::Crate(Alphabet::Crockford),
+};
+
+pub const HEX: Base32 = Base32 {
+    name: "base32:hex",
+    aliases: &["base32hex"],
+    description: "RFC4648 base32 with the extended hex alphabet",
+    alphabet: Chars::Custom {
+        alphabet: HEX_ALPHABET,
+        padding: true,
+    },
+};
+
+pub const HEX_NO_PADDING: Base32 = Base32 {
+    name: "base32:hex|",
+    aliases: &["base32hex|"],
+    description: "RFC4648 base32 with the extended hex alphabet, without padding",
+    alphabet: Chars::Custom {
+        alphabet: HEX_ALPHABET,
+        padding: false,
+    },
+};
+
+pub const Z: Base32 = Base32 {
+    name: "base32:z",
+    aliases: &["zbase32"],
+    description: "z-base-32, as used by Tahoe-LAFS",
+    alphabet: Chars::Custom {
+        alphabet: Z_ALPHABET,
+        padding: true,
+    },
+};
+
+pub const Z_NO_PADDING: Base32 = Base32 {
+    name: "base32:z|",
+    aliases: &["zbase32|"],
+    description: "z-base-32, as used by Tahoe-LAFS, without padding",
+    alphabet: Chars::Custom {
+        alphabet: Z_ALPHABET,
+        padding: false,
+    },
+};
+
+pub const GEOHASH: Base32 = Base32 {
+    name: "base32:geohash",
+    aliases: &["geohash"],
+    description: "base32 with the geohash alphabet",
+    alphabet: Chars::Custom {
+        alphabet: GEOHASH_ALPHABET,
+        padding: true,
+    },
+};
+
+pub const GEOHASH_NO_PADDING: Base32 = Base32 {
+    name: "base32:geohash|",
+    aliases: &["geohash|"],
+    description: "base32 with the geohash alphabet, without padding",
+    alphabet: Chars::Custom {
+        alphabet: GEOHASH_ALPHABET,
+        padding: false,
+    },
+};
+
+impl Base32 {
+    /// Decode `data`, which starts at `offset` in the input.
+    fn decode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        match self.alphabet {
+            Chars::Crate(alphabet) => {
+                let s = str::from_utf8(data).map_err(|e| Error::decode(self.name, e))?;
+                base32::decode(alphabet, s)
+                    .ok_or_else(|| Error::decode(self.name, "invalid base32 data"))
+            }
+            Chars::Custom { alphabet, .. } => decode(self.name, alphabet, data, offset),
+        }
+    }
+}
+
+impl Codec for Base32 {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self.alphabet {
+            Chars::Crate(alphabet) => Ok(base32::encode(alphabet, data).into_bytes()),
+            Chars::Custom { alphabet, padding } => Ok(encode(alphabet, padding, data)),
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.decode_at(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(5, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(8, |data: &[u8], offset| {
+            self.decode_at(data, offset)
+        }))
+    }
+}
+
+fn encode(alphabet: &[u8; 32], padding: bool, data: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    for block in data.chunks(5) {
+        let mut padded = [0; 8];
+        padded[3..3 + block.len()].copy_from_slice(block);
+        let n = u64::from_be_bytes(padded);
+
+        // Each byte needs 8 bits, so the last character may be partly padding bits
+        let chars = (block.len() * 8).div_ceil(5);
+        out.extend((0..chars).map(|i| alphabet[(n >> (35 - 5 * i)) as usize & 31]));
+        if padding {
+            out.resize(out.len() + 8 - chars, b'=');
+        }
+    }
+
+    out
+}
+
+/// Decode base32 in `alphabet`, ignoring case and any padding, where `data` starts at `offset` in
+/// the input.
+fn decode(
+    encoding: &'static str,
+    alphabet: &[u8; 32],
+    data: &[u8],
+    offset: usize,
+) -> Result<Vec<u8>> {
+    let end = data.len() - data.iter().rev().take_while(|c| **c == b'=').count();
+    let mut out = vec![];
+    for (i, block) in data[..end].chunks(8).enumerate() {
+        let mut n = 0u64;
+        for (j, c) in block.iter().enumerate() {
+            let value = alphabet
+                .iter()
+                .position(|a| a.eq_ignore_ascii_case(c))
+                .ok_or_else(|| {
+                    Error::decode(
+                        encoding,
+                        format!(
+                            "invalid character {:?} at offset {}",
+                            *c as char,
+                            offset + i * 8 + j
+                        ),
+                    )
+                })?;
+            n |= (value as u64) << (35 - 5 * j);
+        }
+
+        let len = match block.len() {
+            2 => 1,
+            4 => 2,
+            5 => 3,
+            7 => 4,
+            8 => 5,
+            _ => {
+                return Err(Error::decode(
+                    encoding,
+                    format!("a final group of {} characters is invalid", block.len()),
+                ))
+            }
+        };
+        out.extend(&n.to_be_bytes()[3..3 + len]);
+    }
+
+    Ok(out)
+}
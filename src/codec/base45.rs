@@ -0,0 +1,78 @@
+use super::{radix, Codec};
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+const ALPHABET: &[u8; 45] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ $%*+-./:";
+
+/// RFC 9285 base45, which encodes each pair of bytes as three characters, least significant first.
+pub struct Base45;
+
+impl Base45 {
+    /// Decode `data`, which starts at `offset` in the input.
+    fn decode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        for (i, chunk) in data.chunks(3).enumerate() {
+            let offset = offset + i * 3;
+            let values = radix::values(self.name(), chunk, ALPHABET, offset)?;
+            let n = values.iter().rev().fold(0, |n, v| n * 45 + *v as usize);
+            match chunk.len() {
+                3 if n <= 0xffff => out.extend((n as u16).to_be_bytes()),
+                2 if n <= 0xff => out.push(n as u8),
+                1 => {
+                    return Err(Error::decode(
+                        self.name(),
+                        format!("a single character at offset {} can't be decoded", offset),
+                    ))
+                }
+                _ => {
+                    return Err(Error::decode(
+                        self.name(),
+                        format!("value {} at offset {} is out of range", n, offset),
+                    ))
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+impl Codec for Base45 {
+    fn name(&self) -> &'static str {
+        "base45"
+    }
+
+    fn description(&self) -> &'static str {
+        "RFC 9285 base45, as used in QR codes"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        for pair in data.chunks(2) {
+            let (mut n, len) = match pair {
+                [a, b] => ((*a as usize) << 8 | *b as usize, 3),
+                _ => (pair[0] as usize, 2),
+            };
+            for _ in 0..len {
+                out.push(ALPHABET[n % 45]);
+                n /= 45;
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.decode_at(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(2, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(3, |data: &[u8], offset| {
+            self.decode_at(data, offset)
+        }))
+    }
+}
@@ -0,0 +1,238 @@
+use sha2::{Digest, Sha256};
+
+use super::{radix, Codec, Options};
+use crate::stream::{run, Blocks, Buffered, BufferedWithInfo, Transform};
+use crate::{Error, Result};
+
+const BITCOIN_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const FLICKR_ALPHABET: &[u8; 58] = b"123456789abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+const RIPPLE_ALPHABET: &[u8; 58] = b"rpshnaf39wBUDNEGHJKLM4PQRST7VWXYZ2bcdeCg65jkm8oFqi1tuvAxyz";
+
+/// Monero encodes 8 byte blocks as 11 characters, and shorter final blocks with this many.
+const MONERO_BLOCK_SIZES: [usize; 9] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+
+pub struct Base58 {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    alphabet: &'static [u8; 58],
+    blocks: bool,
+}
+
+pub const BITCOIN: Base58 = Base58 {
+    name: "base58",
+    aliases: &["base58:bitcoin"],
+    description: "Bitcoin's base58, also used by IPFS",
+    alphabet: BITCOIN_ALPHABET,
+    blocks: false,
+};
+
+pub const FLICKR: Base58 = Base58 {
+    name: "base58:flickr",
+    aliases: &[],
+    description: "Flickr's base58, with lowercase letters first",
+    alphabet: FLICKR_ALPHABET,
+    blocks: false,
+};
+
+pub const RIPPLE: Base58 = Base58 {
+    name: "base58:ripple",
+    aliases: &[],
+    description: "Ripple's base58",
+    alphabet: RIPPLE_ALPHABET,
+    blocks: false,
+};
+
+pub const MONERO: Base58 = Base58 {
+    name: "base58:monero",
+    aliases: &[],
+    description: "Monero's base58, which encodes 8 byte blocks as 11 characters",
+    alphabet: BITCOIN_ALPHABET,
+    blocks: true,
+};
+
+impl Base58 {
+    fn encode_block(&self, block: &[u8]) -> Vec<u8> {
+        let width = MONERO_BLOCK_SIZES[block.len()];
+        let digits = radix::to_digits(block, 58);
+        let mut out = vec![self.alphabet[0]; width - digits.len()];
+        out.extend(digits.into_iter().map(|d| self.alphabet[d as usize]));
+        out
+    }
+
+    /// Decode whole blocks, and a final partial one, where `data` starts at `offset` in the input.
+    fn decode_blocks(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        for (i, block) in data.chunks(11).enumerate() {
+            out.extend(self.decode_block(block, offset + i * 11)?);
+        }
+
+        Ok(out)
+    }
+
+    fn decode_block(&self, block: &[u8], offset: usize) -> Result<Vec<u8>> {
+        let size = MONERO_BLOCK_SIZES
+            .iter()
+            .position(|n| *n == block.len())
+            .ok_or_else(|| {
+                Error::decode(
+                    self.name,
+                    format!("invalid block length {} at offset {}", block.len(), offset),
+                )
+            })?;
+
+        let digits = radix::values(self.name, block, self.alphabet, offset)?;
+        let bytes = radix::from_digits(&digits, 58);
+        if bytes.len() > size {
+            return Err(Error::decode(
+                self.name,
+                format!("block at offset {} overflows {} bytes", offset, size),
+            ));
+        }
+
+        let mut out = vec![0; size - bytes.len()];
+        out.extend(bytes);
+        Ok(out)
+    }
+}
+
+impl Codec for Base58 {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn is_big_number(&self) -> bool {
+        !self.blocks
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !self.blocks {
+            return Ok(radix::encode(data, self.alphabet));
+        }
+
+        Ok(data.chunks(8).flat_map(|b| self.encode_block(b)).collect())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !self.blocks {
+            return radix::decode(self.name, data, self.alphabet);
+        }
+
+        self.decode_blocks(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        match self.blocks {
+            true => Box::new(Blocks::new(8, |data: &[u8], _| self.encode(data))),
+            false => Box::new(Buffered::new(|data: &[u8]| self.encode(data))),
+        }
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        match self.blocks {
+            true => Box::new(Blocks::new(11, |data: &[u8], offset| {
+                self.decode_blocks(data, offset)
+            })),
+            false => Box::new(Buffered::new(|data: &[u8]| self.decode(data))),
+        }
+    }
+}
+
+/// Bitcoin's base58 with a version byte before the data and a checksum after it.
+pub struct Base58Check {
+    version: Option<u8>,
+}
+
+pub const BASE58CHECK: Base58Check = Base58Check { version: None };
+
+impl Base58Check {
+    /// The first 4 bytes of the SHA-256 of the SHA-256 of `data`.
+    fn checksum(data: &[u8]) -> [u8; 4] {
+        let hash = Sha256::digest(Sha256::digest(data));
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    /// Decode `data`, returning its version byte and payload.
+    fn decode_checked(&self, data: &[u8]) -> Result<(u8, Vec<u8>)> {
+        let data = radix::decode(self.name(), data, BITCOIN_ALPHABET)?;
+        if data.len() < 5 {
+            return Err(Error::decode(
+                self.name(),
+                "too short for a version byte and checksum",
+            ));
+        }
+
+        let (data, checksum) = data.split_at(data.len() - 4);
+        let expected = Self::checksum(data);
+        if checksum != expected {
+            return Err(Error::decode(
+                self.name(),
+                format!(
+                    "bad checksum, expected {} but found {}",
+                    hex::encode(expected),
+                    hex::encode(checksum)
+                ),
+            ));
+        }
+
+        match self.version {
+            Some(version) if version != data[0] => Err(Error::decode(
+                self.name(),
+                format!("expected version {} but found {}", version, data[0]),
+            )),
+            _ => Ok((data[0], data[1..].to_owned())),
+        }
+    }
+}
+
+impl Codec for Base58Check {
+    fn name(&self) -> &'static str {
+        "base58check"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bitcoin's base58 with a version byte and a checksum, the version is reported when decoding"
+    }
+
+    fn is_big_number(&self) -> bool {
+        true
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "version=N",
+            "the version byte to encode with (0 by default), or to require when decoding",
+        )]
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        Ok(Box::new(Base58Check {
+            version: options.parse_value("version")?.or(self.version),
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut data = [&[self.version.unwrap_or(0)], data].concat();
+        data.extend(Self::checksum(&data));
+        Ok(radix::encode(&data, BITCOIN_ALPHABET))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(BufferedWithInfo::new(|data: &[u8]| {
+            let (version, payload) = self.decode_checked(data)?;
+            Ok((payload, vec![("version", version.to_string())]))
+        }))
+    }
+}
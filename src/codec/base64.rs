@@ -0,0 +1,116 @@
+use base64::{Config, DecodeError};
+
+use super::Codec;
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+pub struct Base64 {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    config: Config,
+}
+
+pub const STANDARD: Base64 = Base64 {
+    name: "base64",
+    aliases: &["base64:standard"],
+    description: "RFC4648 base64",
+    config: base64::STANDARD,
+};
+
+pub const STANDARD_NO_PADDING: Base64 = Base64 {
+    name: "base64|",
+    aliases: &["base64:standard|"],
+    description: "RFC4648 base64 without padding",
+    config: base64::STANDARD_NO_PAD,
+};
+
+pub const URL_SAFE: Base64 = Base64 {
+    name: "base64:url",
+    aliases: &[],
+    description: "RFC4648 base64 with the URL-safe alphabet",
+    config: base64::URL_SAFE,
+};
+
+pub const URL_SAFE_NO_PADDING: Base64 = Base64 {
+    name: "base64:url|",
+    aliases: &[],
+    description: "RFC4648 base64 with the URL-safe alphabet, without padding",
+    config: base64::URL_SAFE_NO_PAD,
+};
+
+pub const BCRYPT: Base64 = Base64 {
+    name: "base64:bcrypt",
+    aliases: &[],
+    description: "base64 with the bcrypt alphabet",
+    config: base64::BCRYPT,
+};
+
+pub const BINHEX: Base64 = Base64 {
+    name: "base64:binhex",
+    aliases: &[],
+    description: "base64 with the BinHex 4.0 alphabet",
+    config: base64::BINHEX,
+};
+
+pub const CRYPT: Base64 = Base64 {
+    name: "base64:crypt",
+    aliases: &[],
+    description: "base64 with the crypt(3) alphabet",
+    config: base64::CRYPT,
+};
+
+pub const IMAP_MUTF7: Base64 = Base64 {
+    name: "base64:imap",
+    aliases: &[],
+    description: "base64 with the IMAP modified UTF-7 alphabet",
+    config: base64::IMAP_MUTF7,
+};
+
+impl Base64 {
+    /// Decode `data`, which starts at `offset` in the input.
+    fn decode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        base64::decode_config(data, self.config).map_err(|e| {
+            let e = match e {
+                DecodeError::InvalidByte(i, b) => DecodeError::InvalidByte(offset + i, b),
+                DecodeError::InvalidLastSymbol(i, b) => {
+                    DecodeError::InvalidLastSymbol(offset + i, b)
+                }
+                e => e,
+            };
+            Error::decode(self.name, e)
+        })
+    }
+}
+
+impl Codec for Base64 {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(base64::encode_config(data, self.config).into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.decode_at(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(3, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(4, |data: &[u8], offset| {
+            self.decode_at(data, offset)
+        }))
+    }
+}
@@ -0,0 +1,442 @@
+use std::str;
+
+use super::{radix, Codec};
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+pub struct Rfc1924;
+
+impl Codec for Rfc1924 {
+    fn name(&self) -> &'static str {
+        "base85"
+    }
+
+    fn description(&self) -> &'static str {
+        "RFC1924 base85"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(base85::encode(data).into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let s = str::from_utf8(data).map_err(|e| Error::decode(self.name(), e))?;
+        base85::decode(s).ok_or_else(|| Error::decode(self.name(), "invalid base85 data"))
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(4, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(5, |data: &[u8], _| self.decode(data)))
+    }
+}
+
+pub struct Ascii85;
+
+impl Codec for Ascii85 {
+    fn name(&self) -> &'static str {
+        "ascii85"
+    }
+
+    fn description(&self) -> &'static str {
+        "Adobe Ascii85, delimited by <~ and ~>, decoding also accepts it without delimiters"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(ascii85::encode(data).into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let data = data
+            .iter()
+            .copied()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+
+        // The delimiters are optional
+        let data = data.strip_prefix(b"<~").unwrap_or(&data);
+        let data = match data.iter().position(|c| *c == b'~') {
+            Some(end) if &data[end..] == b"~>" => &data[..end],
+            Some(end) => {
+                return Err(Error::decode(
+                    self.name(),
+                    format!("unexpected `~` at offset {}", end),
+                ))
+            }
+            None => data,
+        };
+
+        decode_groups(self.name(), data)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Ascii85Encoder {
+            blocks: Blocks::new(4, |data: &[u8], _| Ok(undelimit(self.encode(data)?))),
+            started: false,
+        })
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Ascii85Decoder {
+            buf: vec![],
+            started: false,
+        })
+    }
+}
+
+const Z85_ALPHABET: &[u8; 85] =
+    b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ.-:+=^!/*?&<>()[]{}@%$#";
+
+/// ZeroMQ's Z85, which only encodes multiples of 4 bytes.
+pub struct Z85;
+
+impl Z85 {
+    /// Decode `data`, which starts at `offset` in the input.
+    fn decode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        if !data.len().is_multiple_of(5) {
+            return Err(Error::decode(
+                self.name(),
+                format!("{} characters isn't a multiple of 5", offset + data.len()),
+            ));
+        }
+
+        let mut out = vec![];
+        for (i, block) in data.chunks(5).enumerate() {
+            let offset = offset + i * 5;
+            let digits = radix::values(self.name(), block, Z85_ALPHABET, offset)?;
+            out.extend(decode_group(self.name(), &digits, offset)?);
+        }
+
+        Ok(out)
+    }
+}
+
+impl Codec for Z85 {
+    fn name(&self) -> &'static str {
+        "z85"
+    }
+
+    fn description(&self) -> &'static str {
+        "ZeroMQ Z85, the data must be a multiple of 4 bytes long"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !data.len().is_multiple_of(4) {
+            return Err(Error::encode(
+                self.name(),
+                format!("{} bytes isn't a multiple of 4", data.len()),
+            ));
+        }
+
+        let mut out = vec![];
+        for block in data.chunks(4) {
+            let mut n = u32::from_be_bytes([block[0], block[1], block[2], block[3]]);
+            let mut digits = [0; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = Z85_ALPHABET[(n % 85) as usize];
+                n /= 85;
+            }
+            out.extend(digits);
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.decode_at(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(4, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(5, |data: &[u8], offset| {
+            self.decode_at(data, offset)
+        }))
+    }
+}
+
+/// How many characters btoa writes per line.
+const BTOA_LINE_LENGTH: usize = 78;
+
+/// The ascii85 variant written by the `btoa` tool, between an `xbtoa Begin` header and an
+/// `xbtoa End` trailer that holds the length and checksums of the data.
+pub struct Btoa;
+
+/// The checksums in the trailer of btoa's output.
+#[derive(Debug, Default, PartialEq)]
+struct BtoaChecksums {
+    eor: u32,
+    sum: u32,
+    rot: u32,
+}
+
+impl BtoaChecksums {
+    fn new(data: &[u8]) -> Self {
+        let mut checksums = Self::default();
+        for &b in data {
+            checksums.eor ^= b as u32;
+            checksums.sum = checksums.sum.wrapping_add(b as u32 + 1);
+            checksums.rot = checksums.rot.rotate_left(1).wrapping_add(b as u32);
+        }
+
+        checksums
+    }
+}
+
+impl Codec for Btoa {
+    fn name(&self) -> &'static str {
+        "btoa"
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        &["ascii85:btoa"]
+    }
+
+    fn description(&self) -> &'static str {
+        "ascii85 as written by btoa, with an xbtoa header and a trailer with checksums"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        // btoa pads the last group with zeros, the trailer has the real length
+        let mut encoded = vec![];
+        for block in data.chunks(4) {
+            let mut padded = [0; 4];
+            padded[..block.len()].copy_from_slice(block);
+            let mut n = u32::from_be_bytes(padded);
+            if n == 0 {
+                encoded.push(b'z');
+                continue;
+            }
+
+            let mut digits = [0; 5];
+            for digit in digits.iter_mut().rev() {
+                *digit = b'!' + (n % 85) as u8;
+                n /= 85;
+            }
+            encoded.extend(digits);
+        }
+
+        let mut out = b"xbtoa Begin\n".to_vec();
+        for line in encoded.chunks(BTOA_LINE_LENGTH) {
+            out.extend(line);
+            out.push(b'\n');
+        }
+
+        let checksums = BtoaChecksums::new(data);
+        out.extend(
+            format!(
+                "xbtoa End N {} {:x} E {:x} S {:x} R {:x}\n",
+                data.len(),
+                data.len(),
+                checksums.eor,
+                checksums.sum,
+                checksums.rot
+            )
+            .into_bytes(),
+        );
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let error = |message: &str| Error::decode(self.name(), message);
+
+        let s = str::from_utf8(data).map_err(|e| Error::decode(self.name(), e))?;
+        let s = s
+            .trim_start()
+            .strip_prefix("xbtoa Begin")
+            .ok_or_else(|| error("missing the `xbtoa Begin` header"))?;
+        let (body, trailer) = s
+            .split_once("xbtoa End")
+            .ok_or_else(|| error("missing the `xbtoa End` trailer"))?;
+
+        // N <length> <length in hex> E <eor> S <sum> R <rot>
+        let fields = trailer.split_whitespace().collect::<Vec<_>>();
+        let (len, expected) = match fields[..] {
+            ["N", len, _, "E", eor, "S", sum, "R", rot] => {
+                let hex = |s| u32::from_str_radix(s, 16).ok();
+                match (len.parse::<usize>().ok(), hex(eor), hex(sum), hex(rot)) {
+                    (Some(len), Some(eor), Some(sum), Some(rot)) => {
+                        (len, BtoaChecksums { eor, sum, rot })
+                    }
+                    _ => return Err(error("invalid number in the trailer")),
+                }
+            }
+            _ => return Err(error("invalid `xbtoa End` trailer")),
+        };
+
+        let body = body
+            .bytes()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect::<Vec<_>>();
+        let mut decoded = decode_groups(self.name(), &body)?;
+        if len > decoded.len() || decoded.len() - len >= 4 {
+            return Err(error("the length in the trailer doesn't match the data"));
+        }
+
+        decoded.truncate(len);
+        if BtoaChecksums::new(&decoded) != expected {
+            return Err(error("bad checksum"));
+        }
+
+        Ok(decoded)
+    }
+}
+
+/// Decode groups of 5 ascii85 characters into 4 bytes each, where `z` is short for 4 zero bytes and
+/// `y` for 4 spaces.
+///
+/// A final partial group of N characters decodes to N - 1 bytes.
+fn decode_groups(encoding: &'static str, data: &[u8]) -> Result<Vec<u8>> {
+    let mut out = vec![];
+    let mut group = vec![];
+    for (i, &c) in data.iter().enumerate() {
+        match c {
+            b'z' if group.is_empty() => out.extend([0; 4]),
+            b'y' if group.is_empty() => out.extend([b' '; 4]),
+            b'!'..=b'u' => group.push(c - b'!'),
+            _ => {
+                return Err(Error::decode(
+                    encoding,
+                    format!("invalid character {:?} at offset {}", c as char, i),
+                ))
+            }
+        }
+
+        if group.len() == 5 {
+            out.extend(decode_group(encoding, &group, i - 4)?);
+            group.clear();
+        }
+    }
+
+    match group.len() {
+        0 => {}
+        1 => {
+            return Err(Error::decode(
+                encoding,
+                "a final group of one character can't be decoded",
+            ))
+        }
+        n => {
+            let offset = data.len() - n;
+            group.resize(5, b'u' - b'!');
+            out.extend(&decode_group(encoding, &group, offset)?[..n - 1]);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode 5 digits in base 85 into 4 bytes.
+fn decode_group(encoding: &'static str, digits: &[u8], offset: usize) -> Result<[u8; 4]> {
+    let n = digits.iter().fold(0u64, |n, d| n * 85 + *d as u64);
+    match u32::try_from(n) {
+        Ok(n) => Ok(n.to_be_bytes()),
+        Err(_) => Err(Error::decode(
+            encoding,
+            format!("group at offset {} is too big for 4 bytes", offset),
+        )),
+    }
+}
+
+/// Strip the `<~` and `~>` delimiters from encoded ascii85.
+fn undelimit(mut encoded: Vec<u8>) -> Vec<u8> {
+    encoded.truncate(encoded.len() - 2);
+    encoded.drain(..2);
+    encoded
+}
+
+/// Encodes ascii85 in groups of 4 bytes, delimiting the whole output rather than each group.
+struct Ascii85Encoder<F> {
+    blocks: Blocks<F>,
+    started: bool,
+}
+
+impl<F: FnMut(&[u8], usize) -> Result<Vec<u8>>> Transform for Ascii85Encoder<F> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        if !self.started {
+            out.extend(b"<~");
+            self.started = true;
+        }
+
+        self.blocks.update(input, out)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        if !self.started {
+            out.extend(b"<~");
+        }
+
+        self.blocks.finish(out)?;
+        out.extend(b"~>");
+        Ok(())
+    }
+}
+
+/// Decodes ascii85 a whole number of groups at a time.
+struct Ascii85Decoder {
+    buf: Vec<u8>,
+    started: bool,
+}
+
+impl Ascii85Decoder {
+    /// The length of the longest prefix of `buf` that only contains complete groups.
+    fn complete(&self) -> usize {
+        let (mut end, mut group) = (0, 0);
+        for (i, &c) in self.buf.iter().enumerate() {
+            match c {
+                b'~' => break,
+                b'z' | b'y' if group == 0 => end = i + 1,
+                _ => {
+                    group += 1;
+                    if group == 5 {
+                        end = i + 1;
+                        group = 0;
+                    }
+                }
+            }
+        }
+
+        end
+    }
+}
+
+impl Transform for Ascii85Decoder {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.buf
+            .extend(input.iter().filter(|c| !c.is_ascii_whitespace()));
+        if !self.started {
+            if self.buf.len() < 2 {
+                return Ok(());
+            }
+            if self.buf.starts_with(b"<~") {
+                self.buf.drain(..2);
+            }
+            self.started = true;
+        }
+
+        let n = self.complete();
+        if n > 0 {
+            let mut groups = b"<~".to_vec();
+            groups.extend(self.buf.drain(..n));
+            groups.extend(b"~>");
+            out.extend(Ascii85.decode(&groups)?);
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let mut rest = if self.started || !self.buf.starts_with(b"<~") {
+            b"<~".to_vec()
+        } else {
+            vec![]
+        };
+        rest.append(&mut self.buf);
+        out.extend(Ascii85.decode(&rest)?);
+        Ok(())
+    }
+}
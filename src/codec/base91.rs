@@ -0,0 +1,88 @@
+use super::Codec;
+use crate::{Error, Result};
+
+const ALPHABET: &[u8; 91] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!#$%&()*+,./:;<=>?@[]^_`{|}~\"";
+
+/// Joachim Henke's basE91, which packs 13 or 14 bits into each pair of characters.
+pub struct Base91;
+
+impl Codec for Base91 {
+    fn name(&self) -> &'static str {
+        "base91"
+    }
+
+    fn description(&self) -> &'static str {
+        "basE91, decoding ignores whitespace"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let (mut queue, mut bits) = (0u32, 0);
+        for &b in data {
+            queue |= (b as u32) << bits;
+            bits += 8;
+            if bits > 13 {
+                // Values that can't be mistaken for 14 bits only need 13
+                let mut value = queue & 8191;
+                if value > 88 {
+                    queue >>= 13;
+                    bits -= 13;
+                } else {
+                    value = queue & 16383;
+                    queue >>= 14;
+                    bits -= 14;
+                }
+                out.push(ALPHABET[value as usize % 91]);
+                out.push(ALPHABET[value as usize / 91]);
+            }
+        }
+
+        if bits > 0 {
+            out.push(ALPHABET[queue as usize % 91]);
+            if bits > 7 || queue > 90 {
+                out.push(ALPHABET[queue as usize / 91]);
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let (mut queue, mut bits) = (0u32, 0);
+        let mut first = None;
+        for (i, c) in data.iter().enumerate() {
+            if c.is_ascii_whitespace() {
+                continue;
+            }
+
+            let v = ALPHABET.iter().position(|a| a == c).ok_or_else(|| {
+                Error::decode(
+                    self.name(),
+                    format!("invalid character {:?} at offset {}", *c as char, i),
+                )
+            })? as u32;
+
+            match first.take() {
+                None => first = Some(v),
+                Some(first) => {
+                    let value = first + v * 91;
+                    queue |= value << bits;
+                    bits += if value & 8191 > 88 { 13 } else { 14 };
+                    while bits > 7 {
+                        out.push(queue as u8);
+                        queue >>= 8;
+                        bits -= 8;
+                    }
+                }
+            }
+        }
+
+        if let Some(first) = first {
+            out.push((queue | first << bits) as u8);
+        }
+
+        Ok(out)
+    }
+}
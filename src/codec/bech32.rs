@@ -0,0 +1,243 @@
+use super::{Codec, Options};
+use crate::stream::{run, BufferedWithInfo, Transform};
+use crate::{Error, Result};
+
+const ALPHABET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+const OPTIONS: &[(&str, &str)] = &[
+    (
+        "hrp=PREFIX",
+        "the human-readable part, needed to encode and checked when decoding",
+    ),
+    (
+        "bits=8|5",
+        "whether the data is bytes (the default) or already split into 5-bit values",
+    ),
+];
+
+/// BIP-173's Bech32 and BIP-350's Bech32m, which differ only in the checksum's constant.
+pub struct Bech32 {
+    name: &'static str,
+    description: &'static str,
+    constant: u32,
+    hrp: Option<String>,
+    convert: bool,
+}
+
+pub const BECH32: Bech32 = Bech32 {
+    name: "bech32",
+    description: "BIP-173 Bech32, the human-readable part is reported when decoding",
+    constant: 1,
+    hrp: None,
+    convert: true,
+};
+
+pub const BECH32M: Bech32 = Bech32 {
+    name: "bech32m",
+    description: "BIP-350 Bech32m, the human-readable part is reported when decoding",
+    constant: 0x2bc830a3,
+    hrp: None,
+    convert: true,
+};
+
+fn polymod(values: impl IntoIterator<Item = u8>) -> u32 {
+    let mut chk = 1u32;
+    for v in values {
+        let top = chk >> 25;
+        chk = (chk & 0x1ffffff) << 5 ^ v as u32;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+
+    chk
+}
+
+/// The human-readable part, expanded to be included in the checksum.
+fn expand(hrp: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    hrp.iter()
+        .map(|c| c >> 5)
+        .chain([0])
+        .chain(hrp.iter().map(|c| c & 31))
+}
+
+/// Regroup `data` from `from` bits per value to `to` bits per value.
+///
+/// When `pad` is false, leftover bits must be zero padding, fewer than `from` bits long.
+fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut out = vec![];
+    for &v in data {
+        if (v as u32) >> from != 0 {
+            return None;
+        }
+
+        acc = (acc << from) | v as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & ((1 << to) - 1)) as u8);
+        }
+    }
+
+    if pad {
+        if bits > 0 {
+            out.push(((acc << (to - bits)) & ((1 << to) - 1)) as u8);
+        }
+    } else if bits >= from || (acc << (to - bits)) & ((1 << to) - 1) != 0 {
+        return None;
+    }
+
+    Some(out)
+}
+
+impl Bech32 {
+    /// Decode `data`, returning its human-readable part and data.
+    fn decode_checked(&self, data: &[u8]) -> Result<(String, Vec<u8>)> {
+        let error = |message: String| Error::decode(self.name, message);
+
+        if data.iter().any(u8::is_ascii_lowercase) && data.iter().any(u8::is_ascii_uppercase) {
+            return Err(error("mixes upper and lower case".into()));
+        }
+
+        let data = data.to_ascii_lowercase();
+        let sep = data
+            .iter()
+            .rposition(|c| *c == b'1')
+            .ok_or_else(|| error("missing the `1` separator".into()))?;
+        let (hrp, rest) = (&data[..sep], &data[sep + 1..]);
+        if hrp.is_empty() || hrp.iter().any(|c| !(33..=126).contains(c)) {
+            return Err(error("invalid human-readable part".into()));
+        }
+        if rest.len() < 6 {
+            return Err(error("too short for a checksum".into()));
+        }
+
+        let mut values = vec![];
+        for (i, c) in rest.iter().enumerate() {
+            match ALPHABET.iter().position(|a| a == c) {
+                Some(v) => values.push(v as u8),
+                None => {
+                    return Err(error(format!(
+                        "invalid character {:?} at offset {}",
+                        *c as char,
+                        sep + 1 + i
+                    )))
+                }
+            }
+        }
+
+        if polymod(expand(hrp).chain(values.iter().copied())) != self.constant {
+            return Err(error("bad checksum".into()));
+        }
+
+        let hrp = String::from_utf8_lossy(hrp).into_owned();
+        if let Some(expected) = &self.hrp {
+            if !expected.eq_ignore_ascii_case(&hrp) {
+                return Err(error(format!(
+                    "expected the human-readable part `{}` but found `{}`",
+                    expected, hrp
+                )));
+            }
+        }
+
+        values.truncate(values.len() - 6);
+        if self.convert {
+            values = convert_bits(&values, 5, 8, false)
+                .ok_or_else(|| error("invalid padding in the data".into()))?;
+        }
+
+        Ok((hrp, values))
+    }
+}
+
+impl Codec for Bech32 {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let hrp = match options.get("hrp") {
+            Some(hrp) if hrp.is_empty() || hrp.bytes().any(|c| !(33..=126).contains(&c)) => {
+                return Err(Error::argument(
+                    self.name,
+                    format!("invalid human-readable part `{}`", hrp),
+                ))
+            }
+            Some(hrp) => Some(hrp.to_ascii_lowercase()),
+            None => self.hrp.clone(),
+        };
+        let convert = match options.get("bits") {
+            None | Some("8") => true,
+            Some("5") => false,
+            Some(bits) => {
+                return Err(Error::argument(
+                    self.name,
+                    format!("expected 8 or 5 for bits, got `{}`", bits),
+                ))
+            }
+        };
+
+        Ok(Box::new(Bech32 {
+            name: self.name,
+            description: self.description,
+            constant: self.constant,
+            hrp,
+            convert,
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let hrp = self.hrp.as_ref().ok_or_else(|| {
+            Error::encode(
+                self.name,
+                format!("needs a human-readable part, e.g. `{},hrp=bc`", self.name),
+            )
+        })?;
+
+        let values = match self.convert {
+            true => convert_bits(data, 8, 5, true).unwrap(),
+            false => convert_bits(data, 5, 5, false)
+                .ok_or_else(|| Error::encode(self.name, "data has values above 31"))?,
+        };
+
+        let checksum = polymod(
+            expand(hrp.as_bytes())
+                .chain(values.iter().copied())
+                .chain([0; 6]),
+        ) ^ self.constant;
+
+        let mut out = hrp.as_bytes().to_vec();
+        out.push(b'1');
+        out.extend(values.iter().map(|v| ALPHABET[*v as usize]));
+        out.extend(
+            (0..6)
+                .rev()
+                .map(|i| ALPHABET[(checksum >> (5 * i)) as usize & 31]),
+        );
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(BufferedWithInfo::new(|data: &[u8]| {
+            let (hrp, data) = self.decode_checked(data)?;
+            Ok((data, vec![("hrp", hrp)]))
+        }))
+    }
+}
@@ -0,0 +1,76 @@
+use super::{radix, Codec};
+use crate::Result;
+
+/// The whole input written as one big-endian number, with a leading zero digit for each leading
+/// zero byte.
+pub struct BigNum {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    alphabet: &'static [u8],
+    /// Changes the case of the input before decoding, for alphabets that only have one case.
+    fold_case: Option<FoldCase>,
+}
+
+type FoldCase = fn(&[u8]) -> Vec<u8>;
+
+pub const BASE36: BigNum = BigNum {
+    name: "base36",
+    aliases: &["base36:lower"],
+    description: "base36 with lowercase letters, decoding ignores case",
+    alphabet: b"0123456789abcdefghijklmnopqrstuvwxyz",
+    fold_case: Some(<[u8]>::to_ascii_lowercase),
+};
+
+pub const BASE36_UPPER: BigNum = BigNum {
+    name: "base36:upper",
+    aliases: &[],
+    description: "base36 with uppercase letters, decoding ignores case",
+    alphabet: b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    fold_case: Some(<[u8]>::to_ascii_uppercase),
+};
+
+pub const BASE62: BigNum = BigNum {
+    name: "base62",
+    aliases: &["base62:upper"],
+    description: "base62 with the alphabet 0-9A-Za-z",
+    alphabet: b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+    fold_case: None,
+};
+
+pub const BASE62_LOWER: BigNum = BigNum {
+    name: "base62:lower",
+    aliases: &[],
+    description: "base62 with the alphabet 0-9a-zA-Z",
+    alphabet: b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ",
+    fold_case: None,
+};
+
+impl Codec for BigNum {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn is_big_number(&self) -> bool {
+        true
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(radix::encode(data, self.alphabet))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self.fold_case {
+            Some(fold_case) => radix::decode(self.name, &fold_case(data), self.alphabet),
+            None => radix::decode(self.name, data, self.alphabet),
+        }
+    }
+}
@@ -0,0 +1,568 @@
+use std::str;
+
+use encoding_rs::{DecoderResult, EncoderResult};
+
+use super::{Codec, Options};
+use crate::stream::{run, Transform};
+use crate::{Error, Result};
+
+const OPTIONS: &[(&str, &str)] = &[(
+    "errors=fail|replace|escape",
+    "what to do with characters that can't be encoded: fail (the default), replace them with `?` \
+     or escape them as `&#N;`, malformed input is replaced with U+FFFD unless failing",
+)];
+
+/// What to do with characters that a character encoding can't represent.
+#[derive(Clone, Copy, PartialEq)]
+enum Errors {
+    Fail,
+    Replace,
+    Escape,
+}
+
+impl Errors {
+    fn from_options(encoding: &'static str, options: &Options) -> Result<Self> {
+        match options.get("errors") {
+            None | Some("fail") => Ok(Self::Fail),
+            Some("replace") => Ok(Self::Replace),
+            Some("escape") => Ok(Self::Escape),
+            Some(errors) => Err(Error::argument(
+                encoding,
+                format!(
+                    "expected `fail`, `replace` or `escape` for errors, got `{}`",
+                    errors
+                ),
+            )),
+        }
+    }
+
+    /// Handle malformed input as configured, returning `error` when failing.
+    fn malformed(self, error: Error, out: &mut String) -> Result<()> {
+        match self {
+            Self::Fail => Err(error),
+            Self::Replace | Self::Escape => {
+                out.push(char::REPLACEMENT_CHARACTER);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    /// Each character is a single byte, the same as its code point.
+    Latin1,
+    /// Each character is one or two 16-bit code units, or always one when there's no `surrogates`.
+    Utf16 { big_endian: bool, surrogates: bool },
+}
+
+/// A character encoding for text, which is converted to and from UTF-8.
+///
+/// So to read a UTF-16 file, decode it as `utf16le` and encode it as `utf8`.
+pub struct Charset {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    kind: Kind,
+    errors: Errors,
+}
+
+pub const UTF16LE: Charset = Charset {
+    name: "utf16le",
+    aliases: &["utf-16le"],
+    description: "UTF-16 little endian text",
+    kind: Kind::Utf16 {
+        big_endian: false,
+        surrogates: true,
+    },
+    errors: Errors::Fail,
+};
+
+pub const UTF16BE: Charset = Charset {
+    name: "utf16be",
+    aliases: &["utf-16be"],
+    description: "UTF-16 big endian text",
+    kind: Kind::Utf16 {
+        big_endian: true,
+        surrogates: true,
+    },
+    errors: Errors::Fail,
+};
+
+pub const UCS2: Charset = Charset {
+    name: "ucs2",
+    aliases: &["ucs-2"],
+    description: "UCS-2 little endian text, UTF-16 without the characters above U+FFFF",
+    kind: Kind::Utf16 {
+        big_endian: false,
+        surrogates: false,
+    },
+    errors: Errors::Fail,
+};
+
+pub const LATIN1: Charset = Charset {
+    name: "latin1",
+    aliases: &["latin-1", "iso-8859-1"],
+    description: "ISO-8859-1 text, one byte per character",
+    kind: Kind::Latin1,
+    errors: Errors::Fail,
+};
+
+impl Charset {
+    /// Write `c` in this encoding, returning false if it can't be represented.
+    fn write(&self, c: char, out: &mut Vec<u8>) -> bool {
+        match self.kind {
+            Kind::Latin1 => match u8::try_from(c as u32) {
+                Ok(b) => out.push(b),
+                Err(_) => return false,
+            },
+            Kind::Utf16 {
+                big_endian,
+                surrogates,
+            } => {
+                if !surrogates && c.len_utf16() > 1 {
+                    return false;
+                }
+
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    match big_endian {
+                        true => out.extend(unit.to_be_bytes()),
+                        false => out.extend(unit.to_le_bytes()),
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Write `c` in this encoding, handling it as configured if it can't be represented.
+    fn encode_char(&self, c: char, offset: usize, out: &mut Vec<u8>) -> Result<()> {
+        if self.write(c, out) {
+            return Ok(());
+        }
+
+        match self.errors {
+            Errors::Fail => {
+                return Err(Error::encode(
+                    self.name,
+                    format!(
+                        "{:?} (U+{:04X}) at offset {} can't be represented",
+                        c, c as u32, offset
+                    ),
+                ))
+            }
+            Errors::Replace => {
+                self.write('?', out);
+            }
+            Errors::Escape => {
+                for c in format!("&#{};", c as u32).chars() {
+                    self.write(c, out);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Codec for Charset {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let errors = Errors::from_options(self.name, options)?;
+        Ok(Box::new(Charset { errors, ..*self }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.encoder()], data, true)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        let write = |s: &str, offset: usize, out: &mut Vec<u8>, _| {
+            for (i, c) in s.char_indices() {
+                self.encode_char(c, offset + i, out)?;
+            }
+
+            Ok(())
+        };
+
+        Box::new(TextEncoder::new(self.name, self.errors, write))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(CharsetDecoder {
+            codec: self,
+            partial: vec![],
+            offset: 0,
+        })
+    }
+}
+
+/// Any of the character encodings in the WHATWG Encoding Standard, selected by one of its labels.
+pub struct Whatwg {
+    encoding: &'static encoding_rs::Encoding,
+    errors: Errors,
+}
+
+/// Needs configuring with a label before it can be used.
+pub const WHATWG: Whatwg = Whatwg {
+    encoding: encoding_rs::UTF_8,
+    errors: Errors::Fail,
+};
+
+impl Codec for Whatwg {
+    fn name(&self) -> &'static str {
+        "charset"
+    }
+
+    fn description(&self) -> &'static str {
+        "text in the character encoding with the given WHATWG label, e.g. `charset:shift_jis`"
+    }
+
+    fn argument(&self) -> Option<&'static str> {
+        Some("LABEL")
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, arg: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let label = arg.unwrap_or_default();
+        let encoding = encoding_rs::Encoding::for_label(label.as_bytes()).ok_or_else(|| {
+            Error::argument(self.name(), format!("unknown encoding label `{}`", label))
+        })?;
+        let errors = Errors::from_options(self.name(), options)?;
+
+        // encoding_rs can only decode UTF-16, so use our own
+        let utf16 = if encoding == encoding_rs::UTF_16LE {
+            UTF16LE
+        } else if encoding == encoding_rs::UTF_16BE {
+            UTF16BE
+        } else {
+            return Ok(Box::new(Whatwg { encoding, errors }));
+        };
+
+        Ok(Box::new(Charset { errors, ..utf16 }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.encoder()], data, true)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        let name = self.encoding.name();
+        let mut encoder = self.encoding.new_encoder();
+        let write = move |s: &str, offset: usize, out: &mut Vec<u8>, last: bool| {
+            // Encodings like `replacement`, which labels like iso-2022-kr map to, only decode, and
+            // encoding_rs would write UTF-8 instead
+            if self.encoding.output_encoding() != self.encoding {
+                return Err(Error::encode(name, format!("{} can only be decoded", name)));
+            }
+
+            let mut pos = 0;
+            loop {
+                let needed = encoder
+                    .max_buffer_length_from_utf8_without_replacement(s.len() - pos)
+                    .unwrap_or(s.len() - pos);
+                out.reserve(needed);
+
+                let (result, read) =
+                    encoder.encode_from_utf8_to_vec_without_replacement(&s[pos..], out, last);
+                pos += read;
+                match result {
+                    EncoderResult::InputEmpty => return Ok(()),
+                    EncoderResult::OutputFull => {}
+                    EncoderResult::Unmappable(c) => {
+                        let offset = offset + pos - c.len_utf8();
+                        let replacement = match self.errors {
+                            Errors::Fail => {
+                                return Err(Error::encode(
+                                    name,
+                                    format!(
+                                        "{:?} (U+{:04X}) at offset {} can't be represented",
+                                        c, c as u32, offset
+                                    ),
+                                ))
+                            }
+                            Errors::Replace => "?".to_string(),
+                            Errors::Escape => format!("&#{};", c as u32),
+                        };
+
+                        // The replacements are ASCII, which every encoding can represent
+                        out.reserve(replacement.len() * 4);
+                        let _ = encoder.encode_from_utf8_to_vec_without_replacement(
+                            &replacement,
+                            out,
+                            false,
+                        );
+                    }
+                }
+            }
+        };
+
+        Box::new(TextEncoder::new(name, self.errors, write))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(WhatwgDecoder {
+            codec: self,
+            decoder: self.encoding.new_decoder_without_bom_handling(),
+            offset: 0,
+        })
+    }
+}
+
+/// Reads UTF-8, holding back any character that's split across chunks.
+///
+/// The text is passed to `write` along with its offset, and whether it's the end of the input.
+struct TextEncoder<F> {
+    name: &'static str,
+    errors: Errors,
+    write: F,
+    partial: Vec<u8>,
+    offset: usize,
+}
+
+impl<F> TextEncoder<F> {
+    fn new(name: &'static str, errors: Errors, write: F) -> Self {
+        Self {
+            name,
+            errors,
+            write,
+            partial: vec![],
+            offset: 0,
+        }
+    }
+}
+
+impl<F: FnMut(&str, usize, &mut Vec<u8>, bool) -> Result<()>> TextEncoder<F> {
+    fn encode(&mut self, out: &mut Vec<u8>, finish: bool) -> Result<()> {
+        let mut pos = 0;
+        while pos < self.partial.len() {
+            let (valid, invalid) = match str::from_utf8(&self.partial[pos..]) {
+                Ok(s) => (s, None),
+                Err(e) => {
+                    let valid = str::from_utf8(&self.partial[pos..pos + e.valid_up_to()]).unwrap();
+                    (valid, Some(e.error_len()))
+                }
+            };
+
+            (self.write)(valid, self.offset + pos, out, false)?;
+            pos += valid.len();
+
+            let len = match invalid {
+                None => break,
+                Some(Some(len)) => len,
+                // A character that's split across chunks
+                Some(None) if !finish => break,
+                Some(None) => self.partial.len() - pos,
+            };
+
+            if self.errors == Errors::Fail {
+                return Err(Error::encode(
+                    self.name,
+                    format!("invalid UTF-8 at offset {}", self.offset + pos),
+                ));
+            }
+            (self.write)("\u{fffd}", self.offset + pos, out, false)?;
+            pos += len;
+        }
+
+        self.partial.drain(..pos);
+        self.offset += pos;
+        if finish {
+            (self.write)("", self.offset, out, true)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: FnMut(&str, usize, &mut Vec<u8>, bool) -> Result<()>> Transform for TextEncoder<F> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.partial.extend_from_slice(input);
+        self.encode(out, false)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.encode(out, true)
+    }
+}
+
+/// Writes UTF-8, holding back any code units that are split across chunks.
+struct CharsetDecoder<'a> {
+    codec: &'a Charset,
+    partial: Vec<u8>,
+    offset: usize,
+}
+
+impl CharsetDecoder<'_> {
+    fn decode(&mut self, out: &mut Vec<u8>, finish: bool) -> Result<()> {
+        let codec = self.codec;
+        let mut text = String::new();
+        let (big_endian, surrogates) = match codec.kind {
+            Kind::Latin1 => {
+                text.extend(self.partial.drain(..).map(char::from));
+                out.extend(text.as_bytes());
+                return Ok(());
+            }
+            Kind::Utf16 {
+                big_endian,
+                surrogates,
+            } => (big_endian, surrogates),
+        };
+
+        let mut units = self
+            .partial
+            .chunks_exact(2)
+            .map(|unit| match big_endian {
+                true => u16::from_be_bytes([unit[0], unit[1]]),
+                false => u16::from_le_bytes([unit[0], unit[1]]),
+            })
+            .collect::<Vec<_>>();
+
+        // The first half of a surrogate pair might be followed by the second in the next chunk
+        if !finish && surrogates && matches!(units.last(), Some(0xD800..=0xDBFF)) {
+            units.pop();
+        }
+
+        let mut pos = 0;
+        let mut decoded = |c: Option<char>, units: usize| {
+            let result = match c {
+                Some(c) => {
+                    text.push(c);
+                    Ok(())
+                }
+                None => codec.errors.malformed(
+                    Error::decode(
+                        codec.name,
+                        format!("invalid code unit at offset {}", self.offset + pos),
+                    ),
+                    &mut text,
+                ),
+            };
+            pos += units * 2;
+            result
+        };
+
+        if surrogates {
+            for c in char::decode_utf16(units.iter().copied()) {
+                match c {
+                    Ok(c) => decoded(Some(c), c.len_utf16())?,
+                    Err(_) => decoded(None, 1)?,
+                }
+            }
+        } else {
+            for unit in &units {
+                decoded(char::from_u32(*unit as u32), 1)?;
+            }
+        }
+
+        if finish && pos < self.partial.len() {
+            codec.errors.malformed(
+                Error::decode(
+                    codec.name,
+                    format!(
+                        "odd number of bytes, the last is at offset {}",
+                        self.offset + pos
+                    ),
+                ),
+                &mut text,
+            )?;
+            pos = self.partial.len();
+        }
+
+        out.extend(text.as_bytes());
+        self.partial.drain(..pos);
+        self.offset += pos;
+        Ok(())
+    }
+}
+
+impl Transform for CharsetDecoder<'_> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.partial.extend_from_slice(input);
+        self.decode(out, false)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.decode(out, true)
+    }
+}
+
+/// Decodes text with encoding_rs, which keeps track of sequences split across chunks itself.
+struct WhatwgDecoder<'a> {
+    codec: &'a Whatwg,
+    decoder: encoding_rs::Decoder,
+    offset: usize,
+}
+
+impl WhatwgDecoder<'_> {
+    fn decode(&mut self, input: &[u8], out: &mut Vec<u8>, last: bool) -> Result<()> {
+        let mut text = String::new();
+        let mut pos = 0;
+        loop {
+            let needed = self
+                .decoder
+                .max_utf8_buffer_length_without_replacement(input.len() - pos)
+                .unwrap_or(input.len() - pos);
+            text.reserve(needed);
+
+            let (result, read) =
+                self.decoder
+                    .decode_to_string_without_replacement(&input[pos..], &mut text, last);
+            pos += read;
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => {}
+                DecoderResult::Malformed(len, extra) => {
+                    // The malformed sequence may have started in an earlier chunk
+                    let offset = (self.offset + pos).saturating_sub(len as usize + extra as usize);
+                    let error = Error::decode(
+                        self.codec.encoding.name(),
+                        format!("malformed sequence at offset {}", offset),
+                    );
+                    self.codec.errors.malformed(error, &mut text)?;
+                }
+            }
+        }
+
+        self.offset += input.len();
+        out.extend(text.as_bytes());
+        Ok(())
+    }
+}
+
+impl Transform for WhatwgDecoder<'_> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.decode(input, out, false)
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        self.decode(&[], out, true)
+    }
+}
@@ -0,0 +1,218 @@
+use super::{Codec, Options};
+use crate::stream::{run, Transform};
+use crate::{Error, Result};
+
+const OPTIONS: &[(&str, &str)] = &[
+    ("group=N", "when encoding, put a space after every N digits"),
+    (
+        "order=msb|lsb",
+        "the order of the bits in each byte, most significant first by default",
+    ),
+];
+
+/// Bytes written out as a fixed number of digits each, in a base that's a power of two.
+///
+/// Decoding ignores whitespace and punctuation, so separated digits like `0110 0001` or
+/// `141:142` can be read back.
+pub struct Digits {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    radix: u8,
+    width: usize,
+    group: usize,
+    lsb_first: bool,
+}
+
+pub const BINARY: Digits = Digits {
+    name: "bin",
+    aliases: &["binary"],
+    description: "binary, eight digits per byte",
+    radix: 2,
+    width: 8,
+    group: 0,
+    lsb_first: false,
+};
+
+pub const OCTAL: Digits = Digits {
+    name: "oct",
+    aliases: &["octal"],
+    description: "octal, three digits per byte",
+    radix: 8,
+    width: 3,
+    group: 0,
+    lsb_first: false,
+};
+
+impl Digits {
+    /// Apply the configured bit order to a byte, in either direction.
+    fn order(&self, b: u8) -> u8 {
+        if self.lsb_first {
+            b.reverse_bits()
+        } else {
+            b
+        }
+    }
+
+    /// Turn a byte into its digits.
+    fn digits(&self, b: u8) -> impl Iterator<Item = u8> + '_ {
+        let b = self.order(b) as u32;
+        (0..self.width as u32).rev().map(move |i| {
+            let digit = (b / (self.radix as u32).pow(i)) % self.radix as u32;
+            b'0' + digit as u8
+        })
+    }
+
+    /// Turn the digits of a single byte back into it.
+    fn byte(&self, digits: &[u8]) -> Result<u8> {
+        let value = digits.iter().fold(0u32, |value, d| {
+            value * self.radix as u32 + (d - b'0') as u32
+        });
+        match u8::try_from(value) {
+            Ok(b) => Ok(self.order(b)),
+            Err(_) => Err(Error::decode(
+                self.name,
+                format!(
+                    "`{}` is too big for a byte",
+                    String::from_utf8_lossy(digits)
+                ),
+            )),
+        }
+    }
+}
+
+impl Codec for Digits {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let lsb_first = match options.get("order") {
+            None | Some("msb") => false,
+            Some("lsb") => true,
+            Some(order) => {
+                return Err(Error::argument(
+                    self.name,
+                    format!("expected `msb` or `lsb` for the order, got `{}`", order),
+                ))
+            }
+        };
+
+        Ok(Box::new(Digits {
+            group: options.parse_value("group")?.unwrap_or(self.group),
+            lsb_first,
+            ..*self
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.encoder()], data, true)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(DigitsEncoder {
+            codec: self,
+            written: 0,
+        })
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(DigitsDecoder {
+            codec: self,
+            digits: vec![],
+            offset: 0,
+        })
+    }
+}
+
+/// Writes digits, keeping count of them across chunks so groups line up.
+struct DigitsEncoder<'a> {
+    codec: &'a Digits,
+    written: usize,
+}
+
+impl Transform for DigitsEncoder<'_> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        for &b in input {
+            for digit in self.codec.digits(b) {
+                // A group of 0 never divides evenly, so doesn't add any spaces
+                if self.written > 0 && self.written.checked_rem(self.codec.group) == Some(0) {
+                    out.push(b' ');
+                }
+
+                out.push(digit);
+                self.written += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, _: &mut Vec<u8>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Reads digits, skipping separators and holding on to the digits of a byte split across chunks.
+struct DigitsDecoder<'a> {
+    codec: &'a Digits,
+    digits: Vec<u8>,
+    offset: usize,
+}
+
+impl Transform for DigitsDecoder<'_> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        for &c in input {
+            if (b'0'..b'0' + self.codec.radix).contains(&c) {
+                self.digits.push(c);
+                if self.digits.len() == self.codec.width {
+                    out.push(self.codec.byte(&self.digits)?);
+                    self.digits.clear();
+                }
+            } else if !(c.is_ascii_whitespace() || c.is_ascii_punctuation()) {
+                return Err(Error::decode(
+                    self.codec.name,
+                    format!(
+                        "invalid character {:?} at offset {}",
+                        c as char, self.offset
+                    ),
+                ));
+            }
+
+            self.offset += 1;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, _: &mut Vec<u8>) -> Result<()> {
+        if self.digits.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::decode(
+                self.codec.name,
+                format!(
+                    "expected {} digits per byte, the last byte only has {}",
+                    self.codec.width,
+                    self.digits.len()
+                ),
+            ))
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use hex::FromHexError;
+
+use super::Codec;
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+pub struct Hex;
+
+impl Hex {
+    /// Decode `data`, which starts at `offset` in the input.
+    fn decode_at(&self, data: &[u8], offset: usize) -> Result<Vec<u8>> {
+        hex::decode(data).map_err(|e| {
+            let e = match e {
+                FromHexError::InvalidHexCharacter { c, index } => {
+                    FromHexError::InvalidHexCharacter {
+                        c,
+                        index: offset + index,
+                    }
+                }
+                e => e,
+            };
+            Error::decode(self.name(), e)
+        })
+    }
+}
+
+impl Codec for Hex {
+    fn name(&self) -> &'static str {
+        "hex"
+    }
+
+    fn description(&self) -> &'static str {
+        "lowercase hexadecimal, two digits per byte"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(hex::encode(data).into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.decode_at(data, 0)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(2, |data: &[u8], offset| {
+            self.decode_at(data, offset)
+        }))
+    }
+}
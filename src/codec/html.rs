@@ -0,0 +1,184 @@
+use std::str;
+
+use entities::ENTITIES;
+
+use super::Codec;
+use crate::{Error, Result};
+
+/// Which characters are written as character references when encoding.
+#[derive(Clone, Copy, PartialEq)]
+enum Escape {
+    /// Only the characters that are special in HTML and XML.
+    Minimal,
+    /// The special characters and everything outside of ASCII.
+    NonAscii,
+    /// Every character.
+    All,
+}
+
+/// HTML character references, like `&lt;`, `&#60;` and `&#x3c;`.
+///
+/// Decoding understands every named reference in HTML5, and leaves ampersands that don't start a
+/// reference as they are, like browsers do.
+pub struct Html {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    escape: Escape,
+}
+
+pub const HTML: Html = Html {
+    name: "html",
+    aliases: &["xml"],
+    description: "HTML and XML character references, escaping only `<>&\"'`",
+    escape: Escape::Minimal,
+};
+
+pub const HTML_ASCII: Html = Html {
+    name: "html:ascii",
+    aliases: &[],
+    description: "HTML character references, also escaping non-ASCII characters",
+    escape: Escape::NonAscii,
+};
+
+pub const HTML_ALL: Html = Html {
+    name: "html:all",
+    aliases: &[],
+    description: "HTML character references, escaping every character",
+    escape: Escape::All,
+};
+
+/// The escape for one of the characters that are special in HTML and XML.
+fn special(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        // `&apos;` isn't in HTML 4
+        '\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+impl Html {
+    /// Decode the numeric reference at the start of `data`, which follows `&#`, returning the
+    /// character and how many bytes it used.
+    fn numeric(&self, data: &[u8], offset: usize) -> Result<Option<(char, usize)>> {
+        let (radix, start) = match data.first() {
+            Some(b'x' | b'X') => (16, 1),
+            _ => (10, 0),
+        };
+        let digits = data[start..]
+            .iter()
+            .take_while(|c| (**c as char).is_digit(radix))
+            .count();
+        if digits == 0 {
+            return Ok(None);
+        }
+
+        // The semicolon is optional, like in browsers
+        let end = start + digits;
+        let len = end + usize::from(data.get(end) == Some(&b';'));
+        let invalid = || {
+            Error::decode(
+                self.name,
+                format!(
+                    "invalid character reference `&#{}` at offset {}",
+                    String::from_utf8_lossy(&data[..len]),
+                    offset
+                ),
+            )
+        };
+
+        let digits = str::from_utf8(&data[start..end]).unwrap();
+        let value = u32::from_str_radix(digits, radix).map_err(|_| invalid())?;
+        // Browsers replace NUL and read 128 to 159 as Windows-1252, but everything encoded by
+        // `html:all` needs to decode to what it was
+        let c = char::from_u32(value).ok_or_else(invalid)?;
+
+        Ok(Some((c, len)))
+    }
+}
+
+impl Codec for Html {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        // The special characters are all ASCII, so escaping only them works on any bytes
+        if self.escape == Escape::Minimal {
+            let mut out = vec![];
+            for &b in data {
+                match special(b as char) {
+                    Some(escape) => out.extend(escape.bytes()),
+                    None => out.push(b),
+                }
+            }
+            return Ok(out);
+        }
+
+        let text = str::from_utf8(data).map_err(|e| Error::encode(self.name, e))?;
+        let mut out = String::new();
+        for c in text.chars() {
+            match (self.escape, special(c)) {
+                (Escape::All, _) => out.push_str(&format!("&#{};", c as u32)),
+                (_, Some(escape)) => out.push_str(escape),
+                (Escape::NonAscii, None) if !c.is_ascii() => {
+                    out.push_str(&format!("&#{};", c as u32))
+                }
+                _ => out.push(c),
+            }
+        }
+
+        Ok(out.into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let mut i = 0;
+        while i < data.len() {
+            if data[i] != b'&' {
+                out.push(data[i]);
+                i += 1;
+                continue;
+            }
+
+            if data.get(i + 1) == Some(&b'#') {
+                if let Some((c, len)) = self.numeric(&data[i + 2..], i)? {
+                    out.extend(c.encode_utf8(&mut [0; 4]).bytes());
+                    i += 2 + len;
+                    continue;
+                }
+            }
+
+            // Some old names don't need a semicolon, so the longest match wins, like `&notin;`
+            // instead of `&not`
+            let entity = ENTITIES
+                .iter()
+                .filter(|e| data[i..].starts_with(e.entity.as_bytes()))
+                .max_by_key(|e| e.entity.len());
+            match entity {
+                Some(entity) => {
+                    out.extend(entity.characters.bytes());
+                    i += entity.entity.len();
+                }
+                None => {
+                    out.push(b'&');
+                    i += 1;
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
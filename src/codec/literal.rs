@@ -0,0 +1,356 @@
+use std::str;
+
+use super::Codec;
+use crate::{Error, Result};
+
+/// The language whose string literal syntax is used.
+#[derive(Clone, Copy, PartialEq)]
+enum Language {
+    C,
+    Rust,
+    Python,
+    Json,
+    Shell,
+}
+
+/// String literals, with the quotes, that can be pasted into code, e.g. `b"\x00hi\n"` for Rust.
+///
+/// Printable ASCII is kept as it is and everything else is escaped. Decoding understands all of
+/// the language's escapes, not just the ones used when encoding.
+pub struct Literal {
+    name: &'static str,
+    description: &'static str,
+    language: Language,
+}
+
+pub const C: Literal = Literal {
+    name: "literal:c",
+    description: "a C string literal, like `\"\\x00\\n\"`",
+    language: Language::C,
+};
+
+pub const RUST: Literal = Literal {
+    name: "literal:rust",
+    description: "a Rust byte string literal, like `b\"\\0\\n\"`",
+    language: Language::Rust,
+};
+
+pub const PYTHON: Literal = Literal {
+    name: "literal:python",
+    description: "a Python bytes literal, like `b'\\x00\\n'`",
+    language: Language::Python,
+};
+
+pub const JSON: Literal = Literal {
+    name: "literal:json",
+    description: "a JSON string, like `\"\\u0000\\n\"`, which can only hold UTF-8 text",
+    language: Language::Json,
+};
+
+pub const SHELL: Literal = Literal {
+    name: "literal:shell",
+    description:
+        "a Bash, Zsh or Ksh ANSI-C quoted string, like `$'\\x1b\\n'`, which can't hold NUL",
+    language: Language::Shell,
+};
+
+/// The number of digits in `radix` at the start of `data`, up to `max`.
+fn digits(data: &[u8], radix: u32, max: usize) -> usize {
+    data.iter()
+        .take(max)
+        .take_while(|c| (**c as char).is_digit(radix))
+        .count()
+}
+
+fn value(digits: &[u8], radix: u32) -> u32 {
+    // Callers check the digits and that there aren't too many to overflow
+    u32::from_str_radix(str::from_utf8(digits).unwrap(), radix).unwrap()
+}
+
+impl Literal {
+    /// The text before and after the contents of a literal.
+    fn quotes(&self) -> (&'static str, u8) {
+        match self.language {
+            Language::C | Language::Json => ("\"", b'"'),
+            Language::Rust => ("b\"", b'"'),
+            Language::Python => ("b'", b'\''),
+            Language::Shell => ("$'", b'\''),
+        }
+    }
+
+    fn error(&self, message: String) -> Error {
+        Error::decode(self.name, message)
+    }
+
+    /// Decode the escape that starts at `data[i]`, just after a backslash, returning how many
+    /// bytes it used.
+    fn escape(&self, data: &[u8], i: usize, out: &mut Vec<u8>) -> Result<usize> {
+        let unknown = || {
+            let c = String::from_utf8_lossy(&data[i..]);
+            let c = c.chars().next().unwrap_or_default();
+            self.error(format!("unknown escape `\\{}` at offset {}", c, i - 1))
+        };
+        let out_of_range = |len: usize| {
+            self.error(format!(
+                "escape `\\{}` at offset {} is out of range",
+                String::from_utf8_lossy(&data[i..i + len]),
+                i - 1
+            ))
+        };
+        let byte = |value: u32, len: usize| u8::try_from(value).map_err(|_| out_of_range(len));
+        let c = match data.get(i) {
+            Some(c) => *c,
+            None => return Err(self.error("missing the closing quote".into())),
+        };
+        let rest = &data[i + 1..];
+        let len = match (self.language, c) {
+            (Language::Json, b'\'') => return Err(unknown()),
+            (_, b'\\' | b'"' | b'\'') => {
+                out.push(c);
+                1
+            }
+            (_, b'n') => {
+                out.push(b'\n');
+                1
+            }
+            (_, b'r') => {
+                out.push(b'\r');
+                1
+            }
+            (_, b't') => {
+                out.push(b'\t');
+                1
+            }
+            (Language::Rust, b'0') => {
+                out.push(0);
+                1
+            }
+            (Language::C | Language::Python | Language::Shell, b'a') => {
+                out.push(7);
+                1
+            }
+            (Language::C | Language::Python | Language::Shell, b'v') => {
+                out.push(11);
+                1
+            }
+            (Language::Json, b'/') => {
+                out.push(b'/');
+                1
+            }
+            (Language::C | Language::Shell, b'?') => {
+                out.push(b'?');
+                1
+            }
+            (Language::Shell, b'e' | b'E') => {
+                out.push(0x1b);
+                1
+            }
+            (_, b'b') if self.language != Language::Rust => {
+                out.push(8);
+                1
+            }
+            (_, b'f') if self.language != Language::Rust => {
+                out.push(12);
+                1
+            }
+            // A line continuation
+            (Language::Rust, b'\n') => {
+                1 + rest.iter().take_while(|c| c.is_ascii_whitespace()).count()
+            }
+            (Language::Python, b'\n') => 1,
+            (Language::C | Language::Python | Language::Shell, b'0'..=b'7') => {
+                let len = digits(&data[i..], 8, 3);
+                out.push(byte(value(&data[i..i + len], 8), len)?);
+                len
+            }
+            (Language::Shell, b'c') => match rest.first() {
+                Some(c) if c.is_ascii_graphic() => {
+                    out.push(c & 0x1f);
+                    2
+                }
+                _ => return Err(unknown()),
+            },
+            (_, b'x') if self.language != Language::Json => {
+                // C reads as many hex digits as there are, Bash up to two
+                let max = match self.language {
+                    Language::C => usize::MAX,
+                    _ => 2,
+                };
+                let len = digits(rest, 16, max);
+                let exact = matches!(self.language, Language::Rust | Language::Python);
+                if len == 0 || (exact && len != 2) {
+                    return Err(self.error(format!(
+                        "expected hex digits after `\\x` at offset {}",
+                        i - 1
+                    )));
+                }
+
+                // Leading zeros don't change the value
+                let hex = &rest[..len];
+                let hex = &hex[hex.iter().position(|c| *c != b'0').unwrap_or(len - 1)..];
+                if hex.len() > 2 {
+                    return Err(out_of_range(len + 1));
+                }
+                out.push(value(hex, 16) as u8);
+                1 + len
+            }
+            (Language::Json, b'u') => self.unicode_json(data, i, out)?,
+            (Language::Shell, b'u' | b'U') => {
+                let max = if c == b'u' { 4 } else { 8 };
+                let len = digits(rest, 16, max);
+                if len == 0 {
+                    return Err(unknown());
+                }
+                let c =
+                    char::from_u32(value(&rest[..len], 16)).ok_or_else(|| out_of_range(len + 1))?;
+                out.extend(c.encode_utf8(&mut [0; 4]).bytes());
+                1 + len
+            }
+            // Python and Bash keep unknown escapes as they are
+            (Language::Python | Language::Shell, c) if c.is_ascii() => {
+                out.extend([b'\\', c]);
+                1
+            }
+            _ => return Err(unknown()),
+        };
+
+        Ok(len)
+    }
+
+    /// Decode a JSON `\uXXXX` escape, which is joined with the next one for characters outside
+    /// of the basic multilingual plane.
+    fn unicode_json(&self, data: &[u8], i: usize, out: &mut Vec<u8>) -> Result<usize> {
+        let unit = |at: usize| match data.get(at..at + 4) {
+            Some(hex) if digits(hex, 16, 4) == 4 => Some(value(hex, 16)),
+            _ => None,
+        };
+        let invalid = || self.error(format!("invalid `\\u` escape at offset {}", i - 1));
+
+        let high = unit(i + 1).ok_or_else(invalid)?;
+        let (c, len) = match high {
+            0xd800..=0xdbff => {
+                let low = match data.get(i + 5..i + 7) {
+                    Some(b"\\u") => unit(i + 7).filter(|low| (0xdc00..=0xdfff).contains(low)),
+                    _ => None,
+                };
+                let low = low
+                    .ok_or_else(|| self.error(format!("unpaired surrogate at offset {}", i - 1)))?;
+                (0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00), 11)
+            }
+            _ => (high, 5),
+        };
+        let c = char::from_u32(c)
+            .ok_or_else(|| self.error(format!("unpaired surrogate at offset {}", i - 1)))?;
+
+        out.extend(c.encode_utf8(&mut [0; 4]).bytes());
+        Ok(len)
+    }
+}
+
+impl Codec for Literal {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        // JSON strings are text, so only UTF-8 can be represented
+        if self.language == Language::Json {
+            str::from_utf8(data).map_err(|e| Error::encode(self.name, e))?;
+        }
+
+        // Bash ends the string at a NUL, even an escaped one
+        if self.language == Language::Shell {
+            if let Some(i) = data.iter().position(|b| *b == 0) {
+                return Err(Error::encode(
+                    self.name,
+                    format!("NUL at offset {} can't be in a shell string", i),
+                ));
+            }
+        }
+
+        let (open, close) = self.quotes();
+        let mut out = open.as_bytes().to_vec();
+        let mut hex_escape = false;
+        for &b in data {
+            let escape = match (self.language, b) {
+                (_, b'\\') => "\\\\".into(),
+                (_, b) if b == close => format!("\\{}", b as char),
+                (_, b'\n') => "\\n".into(),
+                (_, b'\r') => "\\r".into(),
+                (_, b'\t') => "\\t".into(),
+                (Language::Rust, 0) => "\\0".into(),
+                (Language::Json, 8) => "\\b".into(),
+                (Language::Json, 12) => "\\f".into(),
+                (Language::Json, b) if b < 0x20 => format!("\\u{:04x}", b),
+                // C keeps reading hex digits after `\x`, so they need escaping too
+                (Language::C, b) if hex_escape && b.is_ascii_hexdigit() => {
+                    format!("\\x{:02x}", b)
+                }
+                // The rest of valid UTF-8 can be in a JSON string as it is
+                (Language::Json, _) | (_, b' '..=b'~') => {
+                    out.push(b);
+                    hex_escape = false;
+                    continue;
+                }
+                (_, b) => format!("\\x{:02x}", b),
+            };
+
+            hex_escape = escape.starts_with("\\x");
+            out.extend(escape.bytes());
+        }
+        out.push(close);
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let start = data
+            .iter()
+            .position(|c| !c.is_ascii_whitespace())
+            .unwrap_or(data.len());
+        let (open, close) = self.quotes();
+        let close = match (self.language, data.get(start..start + 2)) {
+            // Python accepts either quote, and the prefix in either case
+            (Language::Python, Some([b'b' | b'B', q @ (b'\'' | b'"')])) => Some(*q),
+            (Language::Python, _) => None,
+            _ => data[start..].starts_with(open.as_bytes()).then_some(close),
+        };
+        let close = close
+            .ok_or_else(|| self.error(format!("expected a literal starting with `{}`", open)))?;
+
+        let mut out = vec![];
+        let mut i = start + open.len();
+        loop {
+            match data.get(i) {
+                None => return Err(self.error("missing the closing quote".into())),
+                Some(b'\\') => i += 1 + self.escape(data, i + 1, &mut out)?,
+                Some(&c) if c == close => break,
+                Some(&c) if self.language == Language::Json && c < 0x20 => {
+                    return Err(self.error(format!(
+                        "unescaped control character {:#04x} at offset {}",
+                        c, i
+                    )))
+                }
+                Some(&c) => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        // Only whitespace can follow the literal
+        let rest = &data[i + 1..];
+        if let Some(extra) = rest.iter().position(|c| !c.is_ascii_whitespace()) {
+            return Err(self.error(format!(
+                "unexpected text after the closing quote at offset {}",
+                i + 1 + extra
+            )));
+        }
+
+        Ok(out)
+    }
+}
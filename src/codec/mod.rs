@@ -0,0 +1,271 @@
+mod auto;
+mod base122;
+mod base32;
+mod base45;
+mod base58;
+mod base64;
+mod base85;
+mod base91;
+mod bech32;
+mod bignum;
+mod charset;
+mod digits;
+mod hex;
+mod html;
+mod literal;
+mod punycode;
+mod qp;
+mod radix;
+mod rot;
+mod text;
+mod unicode;
+mod url;
+mod uu;
+mod yenc;
+
+use std::str::FromStr;
+
+use crate::stream::{Buffered, Transform};
+use crate::{Error, Result};
+
+/// An encoding that data can be transformed to and from.
+///
+/// To add a new encoding, implement this trait and add an instance of it to `CODECS`.
+pub trait Codec: Send + Sync {
+    /// The name used to select this encoding on the command line.
+    fn name(&self) -> &'static str;
+
+    /// Other names this encoding is known by.
+    fn aliases(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// A short description, shown in `--list`.
+    fn description(&self) -> &'static str;
+
+    /// Whether this encoding writes the whole input as one big number, which takes time quadratic
+    /// in its length to convert, so detection skips long inputs.
+    fn is_big_number(&self) -> bool {
+        false
+    }
+
+    /// What the argument after the name is called, for encodings that need one, like the `N` in
+    /// `rot:N`.
+    fn argument(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The options this encoding accepts after its name, like `group=4` in `bin,group=4`, as pairs
+    /// of `key=VALUE` and a description.
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    /// Create a copy of this encoding that uses the given argument and options, see `argument` and
+    /// `options`.
+    ///
+    /// This is only called with an argument if the encoding takes one, and with options it accepts.
+    fn configure(&self, arg: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let _ = (arg, options);
+        Err(Error::argument(
+            self.name(),
+            "this encoding can't be configured",
+        ))
+    }
+
+    /// Encode raw bytes into this encoding.
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decode data in this encoding back into raw bytes.
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// An incremental version of `encode`.
+    ///
+    /// By default this holds all input in memory until it's finished, encodings that can be
+    /// processed in chunks should override it.
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Buffered::new(|data: &[u8]| self.encode(data)))
+    }
+
+    /// An incremental version of `decode`, see `encoder`.
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Buffered::new(|data: &[u8]| self.decode(data)))
+    }
+}
+
+/// The options given after an encoding's name, like `bin,group=4,order=lsb`.
+#[derive(Debug, Default)]
+pub struct Options<'a> {
+    encoding: &'static str,
+    pairs: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Options<'a> {
+    /// Parse comma separated `key=value` pairs, only allowing the keys that `codec` accepts.
+    pub fn parse(codec: &dyn Codec, s: &'a str) -> Result<Self> {
+        let mut pairs = vec![];
+        for option in s.split(',') {
+            let (key, value) = option.split_once('=').unwrap_or((option, ""));
+            let known = codec
+                .options()
+                .iter()
+                .any(|(usage, _)| usage.split('=').next() == Some(key));
+            if !known {
+                return Err(Error::argument(
+                    codec.name(),
+                    format!("unknown option `{}`", key),
+                ));
+            }
+
+            pairs.push((key, value));
+        }
+
+        Ok(Self {
+            encoding: codec.name(),
+            pairs,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// The value of the last option named `key`.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    }
+
+    /// The value of the last option named `key`, parsed as a `T`.
+    pub fn parse_value<T: FromStr>(&self, key: &str) -> Result<Option<T>> {
+        match self.get(key) {
+            Some(value) => value.parse().map(Some).map_err(|_| {
+                Error::argument(
+                    self.encoding,
+                    format!("invalid value for `{}`: `{}`", key, value),
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Every supported encoding, in the order they're listed in `--list`.
+static CODECS: &[&dyn Codec] = &[
+    &auto::Auto,
+    &text::Raw,
+    // Character encodings
+    &text::Ascii,
+    &text::Utf8,
+    &charset::UTF16LE,
+    &charset::UTF16BE,
+    &charset::UCS2,
+    &charset::LATIN1,
+    &charset::WHATWG,
+    // Digit encodings
+    &hex::Hex,
+    &digits::BINARY,
+    &digits::OCTAL,
+    // Base32
+    &base32::RFC4648,
+    &base32::RFC4648_NO_PADDING,
+    &base32::CROCKFORD,
+    &base32::HEX,
+    &base32::HEX_NO_PADDING,
+    &base32::Z,
+    &base32::Z_NO_PADDING,
+    &base32::GEOHASH,
+    &base32::GEOHASH_NO_PADDING,
+    // Big numbers
+    &bignum::BASE36,
+    &bignum::BASE36_UPPER,
+    &bignum::BASE62,
+    &bignum::BASE62_LOWER,
+    // Base45
+    &base45::Base45,
+    // Base58
+    &base58::BITCOIN,
+    &base58::FLICKR,
+    &base58::RIPPLE,
+    &base58::MONERO,
+    &base58::BASE58CHECK,
+    // Bech32
+    &bech32::BECH32,
+    &bech32::BECH32M,
+    // Base64
+    &base64::STANDARD,
+    &base64::STANDARD_NO_PADDING,
+    &base64::URL_SAFE,
+    &base64::URL_SAFE_NO_PADDING,
+    &base64::BCRYPT,
+    &base64::BINHEX,
+    &base64::CRYPT,
+    &base64::IMAP_MUTF7,
+    // Base85
+    &base85::Rfc1924,
+    &base85::Ascii85,
+    &base85::Z85,
+    &base85::Btoa,
+    &base91::Base91,
+    &base122::Base122,
+    &unicode::BASE2048,
+    &unicode::BASE32768,
+    // Mail and usenet
+    &qp::QP,
+    &qp::QP_LENIENT,
+    &uu::UU,
+    &uu::XX,
+    &yenc::YENC,
+    // Web
+    &url::URL,
+    &url::URL_COMPONENT,
+    &url::URL_FORM,
+    &html::HTML,
+    &html::HTML_ASCII,
+    &html::HTML_ALL,
+    &punycode::Punycode,
+    &punycode::Idna,
+    // String literals
+    &literal::C,
+    &literal::RUST,
+    &literal::PYTHON,
+    &literal::JSON,
+    &literal::SHELL,
+    // Ciphers
+    &rot::ROT13,
+    &rot::ROT47,
+    &rot::ROTN,
+];
+
+/// All registered codecs.
+pub fn registry() -> &'static [&'static dyn Codec] {
+    CODECS
+}
+
+/// Find a codec by its name or one of its aliases.
+pub fn lookup(name: &str) -> Option<&'static dyn Codec> {
+    CODECS
+        .iter()
+        .copied()
+        .find(|c| c.name() == name || c.aliases().contains(&name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::registry;
+
+    #[test]
+    fn names_are_unique() {
+        let mut seen = HashSet::new();
+        for codec in registry() {
+            for name in std::iter::once(&codec.name()).chain(codec.aliases()) {
+                assert!(seen.insert(*name), "duplicate encoding name: {}", name);
+            }
+        }
+    }
+}
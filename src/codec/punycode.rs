@@ -0,0 +1,148 @@
+use std::str;
+
+use idna::punycode;
+
+use super::Codec;
+use crate::{Error, Result};
+
+/// RFC 3492 punycode, which represents Unicode text with only letters, digits and hyphens.
+///
+/// Each line is converted separately, so a list of labels can be converted at once.
+pub struct Punycode;
+
+/// Internationalized domain names, converted with the UTS #46 rules that browsers use, e.g.
+/// `bücher.example` and `xn--bcher-kva.example`.
+///
+/// Like `punycode`, each line is a separate domain.
+pub struct Idna;
+
+/// Split `data` into UTF-8 lines, each with its line break if it has one.
+fn lines(data: &[u8]) -> std::result::Result<Vec<(&str, &str)>, str::Utf8Error> {
+    Ok(str::from_utf8(data)?
+        .split_inclusive('\n')
+        .map(|line| {
+            let len = line.trim_end_matches(['\r', '\n']).len();
+            line.split_at(len)
+        })
+        .collect())
+}
+
+impl Idna {
+    /// An error for `domain`, naming the first label that's invalid on its own.
+    ///
+    /// `idna` doesn't say which rule a label breaks, so the only reason given is punycode that
+    /// doesn't decode, which is checked here.
+    fn error(&self, domain: &str, line: usize) -> String {
+        let label = match domain
+            .split('.')
+            .find(|label| idna::domain_to_ascii(label).is_err())
+        {
+            Some(label) => label,
+            None => return format!("invalid domain `{}` on line {}", domain, line + 1),
+        };
+
+        let invalid_punycode = match label.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("xn--") => {
+                punycode::decode_to_string(&label[4..]).is_none()
+            }
+            _ => false,
+        };
+        match invalid_punycode {
+            true => format!(
+                "invalid label `{}` on line {}: invalid punycode",
+                label,
+                line + 1
+            ),
+            false => format!("invalid label `{}` on line {}", label, line + 1),
+        }
+    }
+}
+
+impl Codec for Punycode {
+    fn name(&self) -> &'static str {
+        "punycode"
+    }
+
+    fn description(&self) -> &'static str {
+        "RFC 3492 punycode, without the `xn--` prefix of domain names"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let lines = lines(data).map_err(|e| Error::encode(self.name(), e))?;
+        let mut out = String::new();
+        for (i, (line, newline)) in lines.into_iter().enumerate() {
+            if !line.is_empty() {
+                let encoded = punycode::encode_str(line).ok_or_else(|| {
+                    Error::encode(self.name(), format!("line {} is too long", i + 1))
+                })?;
+                out.push_str(&encoded);
+            }
+            out.push_str(newline);
+        }
+
+        Ok(out.into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let lines = lines(data).map_err(|e| Error::decode(self.name(), e))?;
+        let mut out = String::new();
+        for (i, (line, newline)) in lines.into_iter().enumerate() {
+            if !line.is_empty() {
+                let decoded = line
+                    .is_ascii()
+                    .then(|| punycode::decode_to_string(line))
+                    .flatten()
+                    .ok_or_else(|| {
+                        Error::decode(
+                            self.name(),
+                            format!("invalid punycode `{}` on line {}", line, i + 1),
+                        )
+                    })?;
+                out.push_str(&decoded);
+            }
+            out.push_str(newline);
+        }
+
+        Ok(out.into_bytes())
+    }
+}
+
+impl Codec for Idna {
+    fn name(&self) -> &'static str {
+        "idna"
+    }
+
+    fn description(&self) -> &'static str {
+        "internationalized domain names, like `xn--bcher-kva.example` for `bücher.example`"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let lines = lines(data).map_err(|e| Error::encode(self.name(), e))?;
+        let mut out = String::new();
+        for (i, (domain, newline)) in lines.into_iter().enumerate() {
+            if !domain.is_empty() {
+                let encoded = idna::domain_to_ascii(domain)
+                    .map_err(|_| Error::encode(self.name(), self.error(domain, i)))?;
+                out.push_str(&encoded);
+            }
+            out.push_str(newline);
+        }
+
+        Ok(out.into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let lines = lines(data).map_err(|e| Error::decode(self.name(), e))?;
+        let mut out = String::new();
+        for (i, (domain, newline)) in lines.into_iter().enumerate() {
+            if !domain.is_empty() {
+                let (decoded, result) = idna::domain_to_unicode(domain);
+                result.map_err(|_| Error::decode(self.name(), self.error(domain, i)))?;
+                out.push_str(&decoded);
+            }
+            out.push_str(newline);
+        }
+
+        Ok(out.into_bytes())
+    }
+}
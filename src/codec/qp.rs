@@ -0,0 +1,180 @@
+use super::Codec;
+use crate::{Error, Result};
+
+/// The longest an encoded line can be, including the `=` of a soft line break.
+const LINE_LENGTH: usize = 76;
+
+/// RFC 2045 quoted-printable, which keeps printable ASCII as it is and escapes other bytes as
+/// `=XX`.
+///
+/// Line breaks in the input are kept, and longer lines are wrapped with soft line breaks of the
+/// same kind, or CRLF if there are none.
+pub struct QuotedPrintable {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    /// Pass malformed escapes and unencoded bytes through as they are, instead of failing.
+    lenient: bool,
+}
+
+pub const QP: QuotedPrintable = QuotedPrintable {
+    name: "qp",
+    aliases: &["quoted-printable"],
+    description: "RFC 2045 quoted-printable",
+    lenient: false,
+};
+
+pub const QP_LENIENT: QuotedPrintable = QuotedPrintable {
+    name: "qp:lenient",
+    aliases: &[],
+    description: "quoted-printable, decoding passes malformed escapes through like most mailers",
+    lenient: true,
+};
+
+/// Split `data` into lines, each with its `\r\n` or `\n` line break if it has one.
+fn lines(data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    data.split_inclusive(|c| *c == b'\n').map(|line| {
+        let len = match line {
+            [.., b'\r', b'\n'] => 2,
+            [.., b'\n'] => 1,
+            _ => 0,
+        };
+        line.split_at(line.len() - len)
+    })
+}
+
+fn hex_value(c: u8, lenient: bool) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'F' => Some(c - b'A' + 10),
+        // Only uppercase is valid, but lowercase is common
+        b'a'..=b'f' if lenient => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
+
+impl Codec for QuotedPrintable {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        // Soft line breaks use the input's line breaks, so the two aren't mixed
+        let soft_break: &[u8] = match lines(data).find(|(_, newline)| !newline.is_empty()) {
+            Some((_, b"\n")) => b"=\n",
+            _ => b"=\r\n",
+        };
+
+        let mut out = vec![];
+        for (line, newline) in lines(data) {
+            let mut len = 0;
+            for (i, &b) in line.iter().enumerate() {
+                let last = i == line.len() - 1;
+                let literal = match b {
+                    // Trailing whitespace is removed by some mail transports
+                    b' ' | b'\t' => !last,
+                    b'=' => false,
+                    b => (33..=126).contains(&b),
+                };
+                let width = if literal { 1 } else { 3 };
+
+                // Leave room for the `=` of a soft line break, unless nothing comes after this
+                if len + width > LINE_LENGTH - 1 && !(last && len + width <= LINE_LENGTH) {
+                    out.extend(soft_break);
+                    len = 0;
+                }
+
+                match literal {
+                    true => out.push(b),
+                    false => out.extend(format!("={:02X}", b).into_bytes()),
+                }
+                len += width;
+            }
+
+            out.extend(newline);
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let mut offset = 0;
+        for (line, newline) in lines(data) {
+            // Whitespace at the end of a line was added in transit
+            let end = line.len()
+                - line
+                    .iter()
+                    .rev()
+                    .take_while(|c| matches!(c, b' ' | b'\t'))
+                    .count();
+            let mut soft_break = false;
+            let mut i = 0;
+            while i < end {
+                let b = line[i];
+                match b {
+                    b'=' if i + 1 == end => {
+                        soft_break = true;
+                        i += 1;
+                        continue;
+                    }
+                    b'=' => {
+                        let value = match line[i + 1..end] {
+                            [hi, lo, ..] => hex_value(hi, self.lenient)
+                                .zip(hex_value(lo, self.lenient))
+                                .map(|(hi, lo)| hi << 4 | lo),
+                            _ => None,
+                        };
+                        match value {
+                            Some(value) => {
+                                out.push(value);
+                                i += 3;
+                            }
+                            None if self.lenient => {
+                                out.push(b);
+                                i += 1;
+                            }
+                            None => {
+                                let escape = &line[i..end.min(i + 3)];
+                                return Err(Error::decode(
+                                    self.name,
+                                    format!(
+                                        "invalid escape `{}` at offset {}",
+                                        String::from_utf8_lossy(escape),
+                                        offset + i
+                                    ),
+                                ));
+                            }
+                        }
+                        continue;
+                    }
+                    b' ' | b'\t' | 33..=126 => out.push(b),
+                    _ if self.lenient => out.push(b),
+                    _ => {
+                        return Err(Error::decode(
+                            self.name,
+                            format!("unencoded byte {:#04x} at offset {}", b, offset + i),
+                        ))
+                    }
+                }
+                i += 1;
+            }
+
+            // Soft line breaks join lines, hard ones are kept
+            if !soft_break {
+                out.extend(newline);
+            }
+            offset += line.len() + newline.len();
+        }
+
+        Ok(out)
+    }
+}
@@ -0,0 +1,139 @@
+//! Conversion between bytes and digits in any base, treating both as big-endian numbers.
+
+use crate::{Error, Result};
+
+/// The largest power of `base` that fits in a `u32`, and how many digits it has.
+///
+/// Converting one of these at a time, rather than one digit, saves most of the passes over the
+/// number, which are what make the conversion slow for long inputs.
+fn big_base(base: u32) -> (u64, usize) {
+    let (mut big, mut width) = (base as u64, 1);
+    while big * base as u64 <= u32::MAX as u64 {
+        big *= base as u64;
+        width += 1;
+    }
+
+    (big, width)
+}
+
+/// Convert `data` into the digits of the same number in `base`, most significant first.
+///
+/// Leading zero bytes don't change the number, so they're dropped, and zero has no digits.
+///
+/// This takes time quadratic in the length of `data`, as every digit depends on all of it.
+pub fn to_digits(data: &[u8], base: u32) -> Vec<u8> {
+    let (big, width) = big_base(base);
+
+    // 32 bit limbs, most significant first
+    let padded = [&[0; 3][..(4 - data.len() % 4) % 4], data].concat();
+    let mut limbs = padded
+        .chunks(4)
+        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+        .skip_while(|limb| *limb == 0)
+        .collect::<Vec<_>>();
+
+    // Divide by `big` until nothing is left, the remainders are the digits, least significant first
+    let mut digits: Vec<u8> = vec![];
+    while !limbs.is_empty() {
+        let mut rem = 0;
+        for limb in limbs.iter_mut() {
+            let n = rem << 32 | *limb as u64;
+            *limb = (n / big) as u32;
+            rem = n % big;
+        }
+        if limbs[0] == 0 {
+            limbs.remove(0);
+        }
+
+        for _ in 0..width {
+            digits.push((rem % base as u64) as u8);
+            rem /= base as u64;
+        }
+    }
+
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits.reverse();
+    digits
+}
+
+/// Convert the digits of a number in `base`, most significant first, into bytes.
+///
+/// Like `to_digits`, leading zeros are dropped, and this takes quadratic time too.
+pub fn from_digits(digits: &[u8], base: u32) -> Vec<u8> {
+    let (_, width) = big_base(base);
+
+    // 32 bit limbs, least significant first, multiplied up by as many digits at a time as fit
+    let mut limbs: Vec<u32> = vec![];
+    let first = match digits.len() % width {
+        0 => width,
+        n => n,
+    };
+    let groups = [&digits[..first.min(digits.len())]]
+        .into_iter()
+        .chain(digits[first.min(digits.len())..].chunks(width));
+    for group in groups {
+        let mut carry = group.iter().fold(0, |n, d| n * base as u64 + *d as u64);
+        let scale = (base as u64).pow(group.len() as u32);
+        for limb in limbs.iter_mut() {
+            let n = *limb as u64 * scale + carry;
+            *limb = n as u32;
+            carry = n >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+    }
+
+    let bytes = limbs
+        .iter()
+        .rev()
+        .flat_map(|limb| limb.to_be_bytes())
+        .collect::<Vec<_>>();
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    bytes[zeros..].to_vec()
+}
+
+/// Encode `data` as a number written with `alphabet`, keeping each leading zero byte as a leading
+/// zero digit.
+pub fn encode(data: &[u8], alphabet: &[u8]) -> Vec<u8> {
+    let zeros = data.iter().take_while(|b| **b == 0).count();
+    let digits = to_digits(&data[zeros..], alphabet.len() as u32);
+    let mut out = vec![alphabet[0]; zeros];
+    out.extend(digits.into_iter().map(|d| alphabet[d as usize]));
+    out
+}
+
+/// The inverse of `encode`.
+pub fn decode(encoding: &'static str, data: &[u8], alphabet: &[u8]) -> Result<Vec<u8>> {
+    let digits = values(encoding, data, alphabet, 0)?;
+    let zeros = digits.iter().take_while(|d| **d == 0).count();
+    let mut bytes = vec![0; zeros];
+    bytes.extend(from_digits(&digits[zeros..], alphabet.len() as u32));
+    Ok(bytes)
+}
+
+/// Look up the value of each character of `data` in `alphabet`, where `data` starts at `offset` in
+/// the input.
+pub fn values(
+    encoding: &'static str,
+    data: &[u8],
+    alphabet: &[u8],
+    offset: usize,
+) -> Result<Vec<u8>> {
+    data.iter()
+        .enumerate()
+        .map(|(i, c)| match alphabet.iter().position(|a| a == c) {
+            Some(value) => Ok(value as u8),
+            None => Err(Error::decode(
+                encoding,
+                format!(
+                    "invalid character {:?} at offset {}",
+                    *c as char,
+                    offset + i
+                ),
+            )),
+        })
+        .collect()
+}
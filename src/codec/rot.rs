@@ -0,0 +1,114 @@
+use super::{Codec, Options};
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+/// Characters that are rotated, as the first character and the number of characters after it.
+type Range = (u8, u8);
+
+const LETTERS: &[Range] = &[(b'A', 26), (b'a', 26)];
+const PRINTABLE: &[Range] = &[(b'!', 94)];
+
+/// A substitution cipher that shifts characters along a range, leaving all others as they are.
+pub struct Rot {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    argument: Option<&'static str>,
+    ranges: &'static [Range],
+    shift: u8,
+}
+
+pub const ROT13: Rot = Rot {
+    name: "rot13",
+    aliases: &[],
+    description: "rotate letters by 13 places",
+    argument: None,
+    ranges: LETTERS,
+    shift: 13,
+};
+
+pub const ROT47: Rot = Rot {
+    name: "rot47",
+    aliases: &[],
+    description: "rotate printable ASCII characters by 47 places",
+    argument: None,
+    ranges: PRINTABLE,
+    shift: 47,
+};
+
+pub const ROTN: Rot = Rot {
+    name: "rot",
+    aliases: &["caesar"],
+    description: "rotate letters by N places, e.g. `rot:3` is the Caesar cipher",
+    argument: Some("N"),
+    ranges: LETTERS,
+    shift: 0,
+};
+
+impl Rot {
+    fn rotate(&self, data: &[u8], forward: bool) -> Vec<u8> {
+        data.iter()
+            .map(|&b| {
+                for &(first, len) in self.ranges {
+                    if (first..first + len).contains(&b) {
+                        let shift = if forward {
+                            self.shift
+                        } else {
+                            len - self.shift
+                        };
+                        return first + (b - first + shift) % len;
+                    }
+                }
+
+                b
+            })
+            .collect()
+    }
+}
+
+impl Codec for Rot {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn argument(&self) -> Option<&'static str> {
+        self.argument
+    }
+
+    fn configure(&self, arg: Option<&str>, _: &Options) -> Result<Box<dyn Codec>> {
+        let arg = arg.unwrap_or_default();
+        let shift = arg
+            .parse::<i64>()
+            .map_err(|_| Error::argument(self.name, format!("expected a number, got `{}`", arg)))?;
+
+        // The ranges of the encodings that take an argument are all the same length
+        Ok(Box::new(Rot {
+            shift: shift.rem_euclid(self.ranges[0].1 as i64) as u8,
+            ..*self
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.rotate(data, true))
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.rotate(data, false))
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.decode(data)))
+    }
+}
@@ -0,0 +1,126 @@
+use std::str;
+
+use super::Codec;
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+pub struct Raw;
+
+impl Codec for Raw {
+    fn name(&self) -> &'static str {
+        "raw"
+    }
+
+    fn description(&self) -> &'static str {
+        "bytes as-is, without any transformation"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_owned())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_owned())
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.decode(data)))
+    }
+}
+
+pub struct Ascii;
+
+impl Codec for Ascii {
+    fn name(&self) -> &'static str {
+        "ascii"
+    }
+
+    fn description(&self) -> &'static str {
+        "7-bit ASCII text"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !data.is_ascii() {
+            return Err(Error::encode(self.name(), "data contains non-ASCII bytes"));
+        }
+
+        Ok(data.to_owned())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !data.is_ascii() {
+            return Err(Error::decode(self.name(), "input contains non-ASCII bytes"));
+        }
+
+        Ok(data.to_owned())
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.encode(data)))
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.decode(data)))
+    }
+}
+
+pub struct Utf8;
+
+impl Codec for Utf8 {
+    fn name(&self) -> &'static str {
+        "utf8"
+    }
+
+    fn description(&self) -> &'static str {
+        "UTF-8 text"
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match str::from_utf8(data) {
+            Ok(_) => Ok(data.to_owned()),
+            Err(e) => Err(Error::encode(self.name(), e)),
+        }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        Ok(data.to_owned())
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Utf8Encoder { partial: vec![] })
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.decode(data)))
+    }
+}
+
+/// Validates UTF-8 a chunk at a time, holding back any character that's split across chunks.
+struct Utf8Encoder {
+    partial: Vec<u8>,
+}
+
+impl Transform for Utf8Encoder {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.partial.extend_from_slice(input);
+        let valid = match str::from_utf8(&self.partial) {
+            Ok(_) => self.partial.len(),
+            // The input ends part way through a character, so wait for the rest of it
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => return Err(Error::encode(Utf8.name(), e)),
+        };
+
+        out.extend(self.partial.drain(..valid));
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        out.extend(Utf8.encode(&self.partial)?);
+        self.partial.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use super::Codec;
+use crate::{Error, Result};
+
+/// qntm's base2048 and base32768, which write many bits per Unicode character and so are compact
+/// when length is counted in characters, like on Twitter.
+///
+/// Input is read most significant bit first. The final character is taken from a smaller `tail`
+/// repertoire if the bits left over fit in it, and any unused bits are set to one.
+pub struct Repertoire {
+    name: &'static str,
+    description: &'static str,
+    bits: u32,
+    /// Ranges of code points, each character's value is its position in these.
+    chars: &'static [(u32, u32)],
+    /// The repertoire for a final character with `bits - 8` bits.
+    tail: &'static [(u32, u32)],
+}
+
+pub const BASE2048: Repertoire = Repertoire {
+    name: "base2048",
+    description: "base2048, which packs 11 bits into each character",
+    bits: 11,
+    chars: BASE2048_CHARS,
+    tail: &[(0x30, 0x37)],
+};
+
+pub const BASE32768: Repertoire = Repertoire {
+    name: "base32768",
+    description: "base32768, which packs 15 bits into each character",
+    bits: 15,
+    chars: BASE32768_CHARS,
+    tail: &[(0x180, 0x19f), (0x240, 0x29f)],
+};
+
+/// The character with `value` in `ranges`.
+fn lookup(ranges: &[(u32, u32)], mut value: u32) -> char {
+    for (first, last) in ranges {
+        if value <= last - first {
+            return char::from_u32(first + value).unwrap();
+        }
+        value -= last - first + 1;
+    }
+
+    unreachable!("value out of range")
+}
+
+/// The value of `c` in `ranges`, if it's there.
+fn position(ranges: &[(u32, u32)], c: char) -> Option<u32> {
+    let mut value = 0;
+    for (first, last) in ranges {
+        if (*first..=*last).contains(&(c as u32)) {
+            return Some(value + c as u32 - first);
+        }
+        value += last - first + 1;
+    }
+
+    None
+}
+
+impl Codec for Repertoire {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = String::new();
+        let (mut queue, mut bits) = (0u32, 0);
+        for &b in data {
+            queue = queue << 8 | b as u32;
+            bits += 8;
+            if bits >= self.bits {
+                bits -= self.bits;
+                out.push(lookup(self.chars, queue >> bits));
+                queue &= (1 << bits) - 1;
+            }
+        }
+
+        if bits > 0 {
+            let (ranges, width) = match bits <= self.bits - 8 {
+                true => (self.tail, self.bits - 8),
+                false => (self.chars, self.bits),
+            };
+            let padding = width - bits;
+            out.push(lookup(ranges, queue << padding | ((1 << padding) - 1)));
+        }
+
+        Ok(out.into_bytes())
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let data = std::str::from_utf8(data).map_err(|e| Error::decode(self.name, e))?;
+        let mut out = vec![];
+        let (mut queue, mut bits) = (0u32, 0);
+        let mut chars = data.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            let (value, width) = match (position(self.chars, c), position(self.tail, c)) {
+                (Some(value), _) => (value, self.bits),
+                (None, Some(value)) if chars.peek().is_none() => (value, self.bits - 8),
+                (None, Some(_)) => {
+                    return Err(Error::decode(
+                        self.name,
+                        format!("final character {:?} at offset {} isn't last", c, i),
+                    ))
+                }
+                (None, None) => {
+                    return Err(Error::decode(
+                        self.name,
+                        format!("invalid character {:?} at offset {}", c, i),
+                    ))
+                }
+            };
+
+            queue = queue << width | value;
+            bits += width;
+            while bits >= 8 {
+                bits -= 8;
+                out.push((queue >> bits) as u8);
+            }
+            queue &= (1 << bits) - 1;
+        }
+
+        if queue != (1 << bits) - 1 {
+            return Err(Error::decode(self.name, "padding bits should all be one"));
+        }
+
+        Ok(out)
+    }
+}
+
+const BASE2048_CHARS: &[(u32, u32)] = &[
+    (0x0038, 0x0039),
+    (0x0041, 0x005a),
+    (0x0061, 0x007a),
+    (0x00c6, 0x00c6),
+    (0x00d0, 0x00d0),
+    (0x00d8, 0x00d8),
+    (0x00de, 0x00df),
+    (0x00e6, 0x00e6),
+    (0x00f0, 0x00f0),
+    (0x00f8, 0x00f8),
+    (0x00fe, 0x00fe),
+    (0x0110, 0x0111),
+    (0x0126, 0x0127),
+    (0x0131, 0x0131),
+    (0x0138, 0x0138),
+    (0x0141, 0x0142),
+    (0x014a, 0x014b),
+    (0x0152, 0x0153),
+    (0x0166, 0x0167),
+    (0x0180, 0x019f),
+    (0x01a2, 0x01ae),
+    (0x01b1, 0x01c3),
+    (0x01dd, 0x01dd),
+    (0x01e4, 0x01e5),
+    (0x01f6, 0x01f7),
+    (0x021c, 0x021d),
+    (0x0220, 0x0225),
+    (0x0234, 0x02af),
+    (0x0370, 0x0373),
+    (0x0376, 0x0377),
+    (0x037b, 0x037d),
+    (0x037f, 0x037f),
+    (0x0391, 0x03a1),
+    (0x03a3, 0x03a9),
+    (0x03b1, 0x03c9),
+    (0x03cf, 0x03cf),
+    (0x03d7, 0x03ef),
+    (0x03f3, 0x03f3),
+    (0x03f7, 0x03f8),
+    (0x03fa, 0x03ff),
+    (0x0402, 0x0402),
+    (0x0404, 0x0406),
+    (0x0408, 0x040b),
+    (0x040f, 0x0418),
+    (0x041a, 0x0438),
+    (0x043a, 0x043b),
+    (0x0440, 0x044f),
+    (0x0452, 0x0452),
+    (0x0454, 0x0456),
+    (0x0458, 0x045b),
+    (0x045f, 0x0475),
+    (0x0478, 0x0481),
+    (0x048a, 0x04c0),
+    (0x04c3, 0x04cf),
+    (0x04d4, 0x04d5),
+    (0x04d8, 0x04d9),
+    (0x04e0, 0x04e1),
+    (0x04e8, 0x04e9),
+    (0x04f6, 0x04f7),
+    (0x04fa, 0x052f),
+    (0x0531, 0x0556),
+    (0x0561, 0x0586),
+    (0x05d0, 0x05ea),
+    (0x05f0, 0x05f2),
+    (0x0620, 0x0621),
+    (0x0627, 0x063f),
+    (0x0641, 0x064a),
+    (0x066e, 0x066f),
+    (0x0671, 0x0674),
+    (0x0679, 0x06bf),
+    (0x06c1, 0x06c1),
+    (0x06c3, 0x06d2),
+    (0x06d5, 0x06d5),
+    (0x06ee, 0x06ef),
+    (0x06fa, 0x06fc),
+    (0x06ff, 0x06ff),
+    (0x0710, 0x0710),
+    (0x0712, 0x072f),
+    (0x074d, 0x07a5),
+    (0x07b1, 0x07b1),
+    (0x07ca, 0x07ea),
+    (0x0800, 0x0815),
+    (0x0840, 0x0858),
+    (0x0860, 0x086a),
+    (0x08a0, 0x08b4),
+    (0x08b6, 0x08bd),
+    (0x0904, 0x0928),
+    (0x092a, 0x0930),
+    (0x0932, 0x0933),
+    (0x0935, 0x0939),
+    (0x093d, 0x093d),
+    (0x0950, 0x0950),
+    (0x0960, 0x0961),
+    (0x0972, 0x0980),
+    (0x0985, 0x098c),
+    (0x098f, 0x0990),
+    (0x0993, 0x09a8),
+    (0x09aa, 0x09b0),
+    (0x09b2, 0x09b2),
+    (0x09b6, 0x09b9),
+    (0x09bd, 0x09bd),
+    (0x09ce, 0x09ce),
+    (0x09e0, 0x09e1),
+    (0x09f0, 0x09f1),
+    (0x09fc, 0x09fc),
+    (0x0a05, 0x0a0a),
+    (0x0a0f, 0x0a10),
+    (0x0a13, 0x0a28),
+    (0x0a2a, 0x0a30),
+    (0x0a32, 0x0a32),
+    (0x0a35, 0x0a35),
+    (0x0a38, 0x0a39),
+    (0x0a5c, 0x0a5c),
+    (0x0a72, 0x0a74),
+    (0x0a85, 0x0a8d),
+    (0x0a8f, 0x0a91),
+    (0x0a93, 0x0aa8),
+    (0x0aaa, 0x0ab0),
+    (0x0ab2, 0x0ab3),
+    (0x0ab5, 0x0ab9),
+    (0x0abd, 0x0abd),
+    (0x0ad0, 0x0ad0),
+    (0x0ae0, 0x0ae1),
+    (0x0af9, 0x0af9),
+    (0x0b05, 0x0b0c),
+    (0x0b0f, 0x0b10),
+    (0x0b13, 0x0b28),
+    (0x0b2a, 0x0b30),
+    (0x0b32, 0x0b33),
+    (0x0b35, 0x0b39),
+    (0x0b3d, 0x0b3d),
+    (0x0b5f, 0x0b61),
+    (0x0b71, 0x0b71),
+    (0x0b83, 0x0b83),
+    (0x0b85, 0x0b8a),
+    (0x0b8e, 0x0b90),
+    (0x0b92, 0x0b93),
+    (0x0b99, 0x0b9a),
+    (0x0b9c, 0x0b9c),
+    (0x0b9e, 0x0b9f),
+    (0x0ba3, 0x0ba4),
+    (0x0ba8, 0x0baa),
+    (0x0bae, 0x0bb9),
+    (0x0bd0, 0x0bd0),
+    (0x0c05, 0x0c0c),
+    (0x0c0e, 0x0c10),
+    (0x0c12, 0x0c28),
+    (0x0c2a, 0x0c39),
+    (0x0c3d, 0x0c3d),
+    (0x0c58, 0x0c5a),
+    (0x0c60, 0x0c61),
+    (0x0c80, 0x0c80),
+    (0x0c85, 0x0c8c),
+    (0x0c8e, 0x0c90),
+    (0x0c92, 0x0ca8),
+    (0x0caa, 0x0cb3),
+    (0x0cb5, 0x0cb9),
+    (0x0cbd, 0x0cbd),
+    (0x0cde, 0x0cde),
+    (0x0ce0, 0x0ce1),
+    (0x0cf1, 0x0cf2),
+    (0x0d05, 0x0d0c),
+    (0x0d0e, 0x0d10),
+    (0x0d12, 0x0d3a),
+    (0x0d3d, 0x0d3d),
+    (0x0d4e, 0x0d4e),
+    (0x0d54, 0x0d56),
+    (0x0d60, 0x0d61),
+    (0x0d7a, 0x0d7f),
+    (0x0d85, 0x0d96),
+    (0x0d9a, 0x0db1),
+    (0x0db3, 0x0dbb),
+    (0x0dbd, 0x0dbd),
+    (0x0dc0, 0x0dc6),
+    (0x0e01, 0x0e30),
+    (0x0e32, 0x0e32),
+    (0x0e40, 0x0e45),
+    (0x0e81, 0x0e82),
+    (0x0e84, 0x0e84),
+    (0x0e87, 0x0e88),
+    (0x0e8a, 0x0e8a),
+    (0x0e8d, 0x0e8d),
+    (0x0e94, 0x0e97),
+    (0x0e99, 0x0e9f),
+    (0x0ea1, 0x0ea3),
+    (0x0ea5, 0x0ea5),
+    (0x0ea7, 0x0ea7),
+    (0x0eaa, 0x0eab),
+    (0x0ead, 0x0eb0),
+    (0x0eb2, 0x0eb2),
+    (0x0ebd, 0x0ebd),
+    (0x0ec0, 0x0ec4),
+    (0x0ede, 0x0edf),
+    (0x0f00, 0x0f00),
+    (0x0f40, 0x0f42),
+    (0x0f44, 0x0f47),
+    (0x0f49, 0x0f4c),
+    (0x0f4e, 0x0f51),
+    (0x0f53, 0x0f56),
+    (0x0f58, 0x0f5b),
+    (0x0f5d, 0x0f68),
+    (0x0f6a, 0x0f6c),
+    (0x0f88, 0x0f8c),
+    (0x1000, 0x1025),
+    (0x1027, 0x102a),
+    (0x103f, 0x103f),
+    (0x1050, 0x1055),
+    (0x105a, 0x105d),
+    (0x1061, 0x1061),
+    (0x1065, 0x1066),
+    (0x106e, 0x1070),
+    (0x1075, 0x1081),
+    (0x108e, 0x108e),
+    (0x10d0, 0x10fa),
+    (0x10fd, 0x1199),
+];
+const BASE32768_CHARS: &[(u32, u32)] = &[
+    (0x04a0, 0x04bf),
+    (0x0500, 0x051f),
+    (0x0680, 0x06bf),
+    (0x0760, 0x079f),
+    (0x07c0, 0x07df),
+    (0x1000, 0x101f),
+    (0x10a0, 0x10bf),
+    (0x1100, 0x115f),
+    (0x1180, 0x119f),
+    (0x11e0, 0x123f),
+    (0x1260, 0x127f),
+    (0x12e0, 0x12ff),
+    (0x1320, 0x133f),
+    (0x13a0, 0x13df),
+    (0x1420, 0x165f),
+    (0x16a0, 0x16df),
+    (0x1780, 0x179f),
+    (0x1820, 0x185f),
+    (0x18c0, 0x18df),
+    (0x1980, 0x199f),
+    (0x19e0, 0x19ff),
+    (0x1a20, 0x1a3f),
+    (0x1bc0, 0x1bdf),
+    (0x1c00, 0x1c1f),
+    (0x1d00, 0x1d1f),
+    (0x21e0, 0x21ff),
+    (0x22c0, 0x22df),
+    (0x2340, 0x23df),
+    (0x2400, 0x241f),
+    (0x2500, 0x275f),
+    (0x2780, 0x27bf),
+    (0x2800, 0x297f),
+    (0x29a0, 0x29bf),
+    (0x2a20, 0x2a5f),
+    (0x2a80, 0x2abf),
+    (0x2ae0, 0x2b5f),
+    (0x2c00, 0x2c1f),
+    (0x2c80, 0x2cdf),
+    (0x2d00, 0x2d1f),
+    (0x2d40, 0x2d5f),
+    (0x2ea0, 0x2edf),
+    (0x31c0, 0x31df),
+    (0x3400, 0x4d9f),
+    (0x4dc0, 0x9fbf),
+    (0xa000, 0xa47f),
+    (0xa4a0, 0xa4bf),
+    (0xa500, 0xa5ff),
+    (0xa640, 0xa65f),
+    (0xa6a0, 0xa6df),
+    (0xa700, 0xa75f),
+    (0xa780, 0xa79f),
+    (0xa840, 0xa85f),
+];
@@ -0,0 +1,150 @@
+use super::{Codec, Options};
+use crate::stream::{Blocks, Transform};
+use crate::{Error, Result};
+
+/// Percent-encoding, which escapes bytes as `%XX` except for letters, digits and a set of other
+/// characters that are kept as they are.
+pub struct Url {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    description: &'static str,
+    /// The characters besides letters and digits that aren't escaped.
+    keep: &'static [u8],
+    /// Whether a space is written as `+`, for HTML forms.
+    plus: bool,
+    /// Extra characters that aren't escaped, from the `safe` option.
+    safe: Vec<u8>,
+}
+
+pub const URL: Url = Url {
+    name: "url",
+    aliases: &["percent"],
+    description: "RFC 3986 percent-encoding, keeping reserved characters like `/` and `?`",
+    // RFC 3986's unreserved characters, and the reserved ones that delimit the parts of a URL
+    keep: b"-._~:/?#[]@!$&'()*+,;=",
+    plus: false,
+    safe: vec![],
+};
+
+pub const URL_COMPONENT: Url = Url {
+    name: "url:component",
+    aliases: &[],
+    description: "percent-encoding for part of a URL, escaping reserved characters",
+    keep: b"-._~",
+    plus: false,
+    safe: vec![],
+};
+
+pub const URL_FORM: Url = Url {
+    name: "url:form",
+    aliases: &["urlencoded"],
+    description: "application/x-www-form-urlencoded, where `+` is a space",
+    keep: b"*-._",
+    plus: true,
+    safe: vec![],
+};
+
+fn hex_value(c: u8) -> Option<u8> {
+    (c as char).to_digit(16).map(|v| v as u8)
+}
+
+impl Url {
+    fn is_kept(&self, b: u8) -> bool {
+        b.is_ascii_alphanumeric() || self.keep.contains(&b) || self.safe.contains(&b)
+    }
+}
+
+impl Codec for Url {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        &[(
+            "safe=CHARS",
+            "more ASCII characters to leave unescaped when encoding, e.g. `safe=/`",
+        )]
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let mut safe = self.safe.clone();
+        for b in options.get("safe").unwrap_or_default().bytes() {
+            // Leaving these unescaped would change how the output is decoded
+            if b == b'%' || (self.plus && b == b'+') || !b.is_ascii_graphic() {
+                return Err(Error::argument(
+                    self.name,
+                    format!("`{}` can't be left unescaped", b as char),
+                ));
+            }
+            safe.push(b);
+        }
+
+        Ok(Box::new(Url {
+            name: self.name,
+            aliases: self.aliases,
+            description: self.description,
+            keep: self.keep,
+            plus: self.plus,
+            safe,
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        for &b in data {
+            match b {
+                b' ' if self.plus => out.push(b'+'),
+                b if self.is_kept(b) => out.push(b),
+                b => out.extend(format!("%{:02X}", b).into_bytes()),
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mut out = vec![];
+        let mut i = 0;
+        while i < data.len() {
+            match data[i] {
+                b'%' => {
+                    let value = match data[i + 1..] {
+                        [hi, lo, ..] => hex_value(hi).zip(hex_value(lo)),
+                        _ => None,
+                    };
+                    let (hi, lo) = value.ok_or_else(|| {
+                        let escape = &data[i..data.len().min(i + 3)];
+                        Error::decode(
+                            self.name,
+                            format!(
+                                "malformed escape `{}` at offset {}",
+                                String::from_utf8_lossy(escape),
+                                i
+                            ),
+                        )
+                    })?;
+                    out.push(hi << 4 | lo);
+                    i += 3;
+                    continue;
+                }
+                b'+' if self.plus => out.push(b' '),
+                b => out.push(b),
+            }
+            i += 1;
+        }
+
+        Ok(out)
+    }
+
+    fn encoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(Blocks::new(1, |data: &[u8], _| self.encode(data)))
+    }
+}
@@ -0,0 +1,204 @@
+use super::{Codec, Options};
+use crate::stream::{run, BufferedWithInfo, Transform};
+use crate::{Error, Result};
+
+/// The most bytes on one line, which encode to 60 characters.
+const LINE_BYTES: usize = 45;
+
+const OPTIONS: &[(&str, &str)] = &[
+    (
+        "name=FILE",
+        "the file name to encode with, by default the name of the input file",
+    ),
+    (
+        "mode=MODE",
+        "the octal file mode to encode with, 644 by default",
+    ),
+];
+
+/// uuencode and xxencode, which split the data into lines that start with their length and are
+/// framed by `begin <mode> <name>` and `end` lines.
+///
+/// When decoding, anything before the `begin` line is skipped and the file name and mode are
+/// reported.
+pub struct Uu {
+    name: &'static str,
+    description: &'static str,
+    alphabet: &'static [u8; 64],
+    file: Option<String>,
+    mode: Option<String>,
+}
+
+pub const UU: Uu = Uu {
+    name: "uu",
+    description: "uuencode, the file name and mode are reported when decoding",
+    // Spaces get stripped from the ends of lines, so zero is written as a backtick instead
+    alphabet: b"`!\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_",
+    file: None,
+    mode: None,
+};
+
+pub const XX: Uu = Uu {
+    name: "xx",
+    description: "xxencode, the file name and mode are reported when decoding",
+    alphabet: b"+-0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+    file: None,
+    mode: None,
+};
+
+impl Uu {
+    /// The value of the character `c`.
+    fn value(&self, c: u8) -> Option<u8> {
+        match self.alphabet.iter().position(|a| *a == c) {
+            Some(value) => Some(value as u8),
+            // Older uuencoders write zero as a space
+            None if self.alphabet[0] == b'`' && c == b' ' => Some(0),
+            None => None,
+        }
+    }
+
+    /// Decode `data`, returning the file name, mode and contents.
+    fn decode_file(&self, data: &[u8]) -> Result<(String, String, Vec<u8>)> {
+        let error = |message: String| Error::decode(self.name, message);
+
+        let mut lines = data
+            .split(|c| *c == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+            .enumerate();
+        let (_, header) = lines
+            .find(|(_, line)| line.starts_with(b"begin "))
+            .ok_or_else(|| error("missing the `begin <mode> <name>` line".into()))?;
+        let header = String::from_utf8_lossy(&header[6..]).into_owned();
+        let (mode, file) = header
+            .split_once(' ')
+            .filter(|(mode, _)| !mode.is_empty() && mode.bytes().all(|c| matches!(c, b'0'..=b'7')))
+            .ok_or_else(|| error(format!("invalid header `begin {}`", header)))?;
+
+        let mut out = vec![];
+        for (i, line) in lines {
+            if line == b"end" {
+                return Ok((file.to_string(), mode.to_string(), out));
+            }
+
+            // Blank lines, and a final line without a length, can appear before `end`
+            let (&len, line) = match line.split_first() {
+                Some(split) => split,
+                None => continue,
+            };
+            let len = self.value(len).ok_or_else(|| {
+                error(format!(
+                    "invalid length character {:?} on line {}",
+                    len as char,
+                    i + 1
+                ))
+            })? as usize;
+
+            // Trailing spaces may have been stripped in transit, so missing characters are zeros
+            let mut values = vec![0; len.div_ceil(3) * 4];
+            for (value, c) in values.iter_mut().zip(line) {
+                *value = self.value(*c).ok_or_else(|| {
+                    error(format!(
+                        "invalid character {:?} on line {}",
+                        *c as char,
+                        i + 1
+                    ))
+                })?;
+            }
+
+            for group in values.chunks(4) {
+                let n = group.iter().fold(0u32, |n, v| n << 6 | *v as u32);
+                out.extend(&n.to_be_bytes()[1..]);
+            }
+            out.truncate(out.len() - values.len() / 4 * 3 + len);
+        }
+
+        Err(error("missing the `end` line".into()))
+    }
+}
+
+impl Codec for Uu {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let file = options
+            .get("name")
+            .map(str::to_string)
+            .or_else(|| self.file.clone());
+        if let Some(file) = &file {
+            if file.is_empty() || file.contains(['\r', '\n']) {
+                return Err(Error::argument(
+                    self.name,
+                    format!("invalid file name `{}`", file),
+                ));
+            }
+        }
+
+        let mode = options
+            .get("mode")
+            .map(str::to_string)
+            .or_else(|| self.mode.clone());
+        if let Some(mode) = &mode {
+            if mode.is_empty() || mode.len() > 4 || !mode.bytes().all(|c| matches!(c, b'0'..=b'7'))
+            {
+                return Err(Error::argument(
+                    self.name,
+                    format!("expected an octal mode, got `{}`", mode),
+                ));
+            }
+        }
+
+        Ok(Box::new(Uu {
+            name: self.name,
+            description: self.description,
+            alphabet: self.alphabet,
+            file,
+            mode,
+        }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let mode = self.mode.as_deref().unwrap_or("644");
+        let file = self.file.as_deref().unwrap_or("data");
+        let mut out = format!("begin {} {}\n", mode, file).into_bytes();
+        for line in data.chunks(LINE_BYTES) {
+            out.push(self.alphabet[line.len()]);
+            for group in line.chunks(3) {
+                let mut padded = [0; 4];
+                padded[1..1 + group.len()].copy_from_slice(group);
+                let n = u32::from_be_bytes(padded);
+                out.extend(
+                    (0..4)
+                        .rev()
+                        .map(|i| self.alphabet[(n >> (6 * i)) as usize & 63]),
+                );
+            }
+            out.push(b'\n');
+        }
+
+        // An empty line marks the end of the data
+        out.push(self.alphabet[0]);
+        out.extend(b"\nend\n");
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(BufferedWithInfo::new(|data: &[u8]| {
+            let (file, mode, data) = self.decode_file(data)?;
+            Ok((data, vec![("name", file), ("mode", mode)]))
+        }))
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use super::{Codec, Options};
+use crate::stream::{run, BufferedWithInfo, Transform};
+use crate::{Error, Result};
+
+const OPTIONS: &[(&str, &str)] = &[
+    (
+        "name=FILE",
+        "the file name to encode with, by default the name of the input file",
+    ),
+    ("line=N", "the length of encoded lines, 128 by default"),
+];
+
+/// yEnc, which writes each byte shifted by 42 and only escapes the few that would break a line of
+/// text.
+///
+/// Decoding checks the sizes and checksums in the `=yend` lines, and reassembles multipart files
+/// from all of their parts, in any order.
+pub struct YEnc {
+    file: Option<String>,
+    line: usize,
+}
+
+pub const YENC: YEnc = YEnc {
+    file: None,
+    line: 128,
+};
+
+/// One `=ybegin` to `=yend` section of the input.
+struct Part {
+    file: String,
+    /// The size of the whole file.
+    size: usize,
+    /// The offset of this part in the file, from 0.
+    begin: usize,
+    data: Vec<u8>,
+    /// The checksum of the whole file, if it was given.
+    crc32: Option<u32>,
+}
+
+/// The `key=value` pairs of a `=y` line.
+///
+/// `name` is always last and can contain spaces, so it takes the rest of the line.
+fn keywords(line: &str) -> HashMap<&str, &str> {
+    let (line, name) = match line.split_once(" name=") {
+        Some((line, name)) => (line, Some(name)),
+        None => (line, None),
+    };
+
+    line.split_whitespace()
+        .filter_map(|pair| pair.split_once('='))
+        .chain(name.map(|name| ("name", name.trim())))
+        .collect()
+}
+
+impl YEnc {
+    fn error(&self, message: String) -> Error {
+        Error::decode(self.name(), message)
+    }
+
+    /// The value of `key` in a `=y` line, which must be present.
+    fn number(&self, keywords: &HashMap<&str, &str>, key: &str, radix: u32) -> Result<u64> {
+        let value = keywords
+            .get(key)
+            .ok_or_else(|| self.error(format!("missing `{}`", key)))?;
+        u64::from_str_radix(value, radix)
+            .map_err(|_| self.error(format!("invalid value for `{}`: `{}`", key, value)))
+    }
+
+    /// Decode every part in `data`, in the order they appear.
+    fn parts(&self, data: &[u8]) -> Result<Vec<Part>> {
+        let mut lines = data
+            .split(|c| *c == b'\n')
+            .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+
+        let mut parts = vec![];
+        while let Some(line) = lines.next() {
+            let begin = match line.strip_prefix(b"=ybegin ") {
+                Some(begin) => String::from_utf8_lossy(begin).into_owned(),
+                None => continue,
+            };
+            let begin = keywords(&begin);
+            let size = self.number(&begin, "size", 10)? as usize;
+            let file = begin
+                .get("name")
+                .ok_or_else(|| self.error("missing `name`".into()))?
+                .to_string();
+
+            // Multipart files say where each part goes on the next line
+            let (offset, end) = match begin.contains_key("part") {
+                true => {
+                    let line = lines.next().unwrap_or_default();
+                    let part = line
+                        .strip_prefix(b"=ypart ")
+                        .ok_or_else(|| self.error("missing the `=ypart` line".into()))?;
+                    let part = String::from_utf8_lossy(part);
+                    let part = keywords(&part);
+                    let begin = self.number(&part, "begin", 10)? as usize;
+                    let end = self.number(&part, "end", 10)? as usize;
+                    if begin == 0 || begin > end || end > size {
+                        return Err(self.error(format!("invalid part range {}-{}", begin, end)));
+                    }
+                    (begin - 1, end)
+                }
+                false => (0, size),
+            };
+
+            let mut decoded = vec![];
+            let trailer = loop {
+                let line = lines
+                    .next()
+                    .ok_or_else(|| self.error(format!("missing the `=yend` line for {}", file)))?;
+                if let Some(trailer) = line.strip_prefix(b"=yend") {
+                    break String::from_utf8_lossy(trailer).into_owned();
+                }
+
+                let mut bytes = line.iter();
+                while let Some(&b) = bytes.next() {
+                    let b = match b {
+                        b'=' => bytes.next().map_or(0, |b| b.wrapping_sub(64)),
+                        b => b,
+                    };
+                    decoded.push(b.wrapping_sub(42));
+                }
+            };
+
+            let trailer = keywords(&trailer);
+            let expected = self.number(&trailer, "size", 10)? as usize;
+            if decoded.len() != expected || decoded.len() != end - offset {
+                return Err(self.error(format!(
+                    "expected {} bytes in {} but found {}",
+                    expected,
+                    file,
+                    decoded.len()
+                )));
+            }
+
+            // Single part files put their checksum in `crc32`, multipart files in `pcrc32`
+            let multipart = begin.contains_key("part");
+            let key = if multipart { "pcrc32" } else { "crc32" };
+            if trailer.contains_key(key) {
+                let expected = self.number(&trailer, key, 16)? as u32;
+                let actual = crc32fast::hash(&decoded);
+                if actual != expected {
+                    return Err(self.error(format!(
+                        "bad {} for {}, expected {:08x} but found {:08x}",
+                        key, file, expected, actual
+                    )));
+                }
+            }
+
+            // A multipart file's `crc32` is for the whole file, so it's checked once it's
+            // reassembled
+            let crc32 = match multipart && trailer.contains_key("crc32") {
+                true => Some(self.number(&trailer, "crc32", 16)? as u32),
+                false => None,
+            };
+
+            parts.push(Part {
+                file,
+                size,
+                begin: offset,
+                data: decoded,
+                crc32,
+            });
+        }
+
+        Ok(parts)
+    }
+
+    /// Decode `data` and put its parts back together, returning the file name and contents.
+    fn decode_file(&self, data: &[u8]) -> Result<(String, Vec<u8>)> {
+        let mut parts = self.parts(data)?;
+        let first = parts
+            .first()
+            .ok_or_else(|| self.error("missing the `=ybegin` line".into()))?;
+        let (file, size) = (first.file.clone(), first.size);
+        if let Some(other) = parts.iter().find(|p| p.file != file || p.size != size) {
+            return Err(self.error(format!("found parts of both {} and {}", file, other.file)));
+        }
+
+        parts.sort_by_key(|p| p.begin);
+        let mut out = Vec::with_capacity(size);
+        for part in &parts {
+            if part.begin < out.len() {
+                return Err(self.error(format!(
+                    "{} has overlapping parts at byte {}",
+                    file,
+                    part.begin + 1
+                )));
+            }
+            if part.begin > out.len() {
+                return Err(self.error(format!(
+                    "{} is missing bytes {} to {}",
+                    file,
+                    out.len() + 1,
+                    part.begin
+                )));
+            }
+            out.extend(&part.data);
+        }
+        if out.len() != size {
+            return Err(self.error(format!(
+                "{} is missing bytes {} to {}",
+                file,
+                out.len() + 1,
+                size
+            )));
+        }
+
+        if let Some(expected) = parts.iter().find_map(|p| p.crc32) {
+            let actual = crc32fast::hash(&out);
+            if actual != expected {
+                return Err(self.error(format!(
+                    "bad crc32 for {}, expected {:08x} but found {:08x}",
+                    file, expected, actual
+                )));
+            }
+        }
+
+        Ok((file, out))
+    }
+}
+
+impl Codec for YEnc {
+    fn name(&self) -> &'static str {
+        "yenc"
+    }
+
+    fn description(&self) -> &'static str {
+        "yEnc, multipart files are reassembled and the file name is reported when decoding"
+    }
+
+    fn options(&self) -> &'static [(&'static str, &'static str)] {
+        OPTIONS
+    }
+
+    fn configure(&self, _: Option<&str>, options: &Options) -> Result<Box<dyn Codec>> {
+        let file = options
+            .get("name")
+            .map(str::to_string)
+            .or_else(|| self.file.clone());
+        if let Some(file) = &file {
+            if file.trim().is_empty() || file.contains(['\r', '\n']) {
+                return Err(Error::argument(
+                    self.name(),
+                    format!("invalid file name `{}`", file),
+                ));
+            }
+        }
+
+        let line = options.parse_value("line")?.unwrap_or(self.line);
+        if line < 2 {
+            return Err(Error::argument(
+                self.name(),
+                "lines need room for at least two characters",
+            ));
+        }
+
+        Ok(Box::new(YEnc { file, line }))
+    }
+
+    fn encode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let file = self.file.as_deref().unwrap_or("data");
+        let mut out = format!(
+            "=ybegin line={} size={} name={}\n",
+            self.line,
+            data.len(),
+            file
+        )
+        .into_bytes();
+
+        let mut line = vec![];
+        for (i, b) in data.iter().enumerate() {
+            let b = b.wrapping_add(42);
+            let last = line.len() + 1 >= self.line || i == data.len() - 1;
+            let escape = match b {
+                b'\0' | b'\n' | b'\r' | b'=' => true,
+                // These can be lost or changed at the start or end of a line
+                b'\t' | b' ' => line.is_empty() || last,
+                b'.' => line.is_empty(),
+                _ => false,
+            };
+            if escape {
+                line.extend([b'=', b.wrapping_add(64)]);
+            } else {
+                line.push(b);
+            }
+
+            if line.len() >= self.line {
+                out.append(&mut line);
+                out.push(b'\n');
+            }
+        }
+        if !line.is_empty() {
+            out.append(&mut line);
+            out.push(b'\n');
+        }
+
+        out.extend(
+            format!(
+                "=yend size={} crc32={:08x}\n",
+                data.len(),
+                crc32fast::hash(data)
+            )
+            .into_bytes(),
+        );
+        Ok(out)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<u8>> {
+        run(&mut [self.decoder()], data, true)
+    }
+
+    fn decoder(&self) -> Box<dyn Transform + '_> {
+        Box::new(BufferedWithInfo::new(|data: &[u8]| {
+            let (file, data) = self.decode_file(data)?;
+            Ok((data, vec![("name", file)]))
+        }))
+    }
+}
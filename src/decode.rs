@@ -1,148 +1,438 @@
-use std::str;
+use std::slice;
 
-use anyhow::{bail, Result};
-
-use crate::cli::Encoding;
-
-fn base32(alphabet: base32::Alphabet, data: &[u8]) -> Result<Vec<u8>> {
-    match base32::decode(alphabet, str::from_utf8(data)?) {
-        Some(r) => Ok(r),
-        None => bail!("Failed to decode base32"),
-    }
-}
+use crate::stream::{self, Info};
+use crate::{Encoding, Result};
 
 pub fn decode(enc: &Encoding, data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
-    let data = data.as_ref();
-    Ok(match enc {
-        Encoding::Raw => data.to_owned(),
-        Encoding::ASCII => {
-            if !data.is_ascii() {
-                bail!(
-                    "Input data contains non-ASCII bytes, and therefore cannot be decoded as ASCII"
-                );
-            }
-
-            data.to_owned()
-        }
-        Encoding::UTF8 => data.to_owned(),
-        Encoding::Hex => hex::decode(&data)?,
-
-        Encoding::Base32Crockford => base32(base32::Alphabet::Crockford, &data)?,
-        Encoding::Base32Rfc4648 => base32(base32::Alphabet::RFC4648 { padding: true }, &data)?,
-        Encoding::Base32Rfc4648NoPadding => {
-            base32(base32::Alphabet::RFC4648 { padding: false }, &data)?
-        }
+    enc.codec().decode(data.as_ref())
+}
 
-        Encoding::Base64Bcrypt => base64::decode_config(&data, base64::BCRYPT)?,
-        Encoding::Base64Binhex => base64::decode_config(&data, base64::BINHEX)?,
-        Encoding::Base64Crypt => base64::decode_config(&data, base64::CRYPT)?,
-        Encoding::Base64ImapMutf7 => base64::decode_config(&data, base64::IMAP_MUTF7)?,
-        Encoding::Base64Standard => base64::decode_config(&data, base64::STANDARD)?,
-        Encoding::Base64StandardNoPadding => base64::decode_config(&data, base64::STANDARD_NO_PAD)?,
-        Encoding::Base64UrlSafe => base64::decode_config(&data, base64::URL_SAFE)?,
-        Encoding::Base64UrlSafeNoPadding => base64::decode_config(&data, base64::URL_SAFE_NO_PAD)?,
-
-        Encoding::Base85Rfc1924 => match base85::decode(str::from_utf8(&data)?) {
-            Some(data) => data,
-            None => bail!("Failed to decode base85"),
-        },
-        Encoding::Base85Ascii => match ascii85::decode(str::from_utf8(&data)?) {
-            Ok(data) => data,
-            Err(e) => bail!("Failed to decode ascii85: {}", e),
-        },
-    })
+/// Like `decode`, but also returns the details found in the input, like the version byte of
+/// base58check.
+pub fn decode_with_info(enc: &Encoding, data: impl AsRef<[u8]>) -> Result<(Vec<u8>, Vec<Info>)> {
+    let mut stages = [enc.codec().decoder()];
+    let decoded = stream::run(&mut stages, data.as_ref(), true)?;
+    Ok((decoded, stream::info(slice::from_ref(enc), &stages)))
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{cli::Encoding, decode::decode};
+    use crate::decode::{decode, decode_with_info};
+    use crate::Info;
+
+    #[test]
+    fn it_rejects() {
+        let t = |e: &str, inp: &[u8]| assert!(decode(&e.parse().unwrap(), inp).is_err());
+
+        t("bin", b"0110000");
+        t("bin", b"01100002");
+        t("oct", b"400");
+        t("oct", b"14a");
+
+        t("base32:hex", b"D1KW====");
+        t("base32:hex|", b"D1K");
+        t("geohash", b"ailo");
+
+        t("base36", b"hello-world");
+        t("base62", b"hello_world");
+
+        t("ascii85", b"<~uuuuu~>");
+        t("ascii85", b"<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~x");
+        t("ascii85", b"<~ab z~>");
+        t("z85", b"HelloWorl");
+        t("z85", b"Hello~orld");
+        t(
+            "btoa",
+            b"xbtoa Begin\nBOu!rD]j7BEbo7d\nxbtoa End N 11 b E 20 S 468 R 339ac\n",
+        );
+        t(
+            "btoa",
+            b"xbtoa Begin\nBOu!rD]j7BEbo7d\nxbtoa End N 12 c E 20 S 467 R 339ac\n",
+        );
+        t(
+            "btoa",
+            b"BOu!rD]j7BEbo7d\nxbtoa End N 11 b E 20 S 467 R 339ac\n",
+        );
+
+        t("base91", b"fPN-d");
+        t("base122", b"\xc2");
+        t("base122", "\u{d80}".as_bytes());
+        t("base2048", "\u{6c5}3\u{54c}".as_bytes());
+        t("base2048", "\u{6c5}\u{54d}".as_bytes());
+        t("base32768", b"hi");
+
+        t("uu", b"#0V%T\n`\nend\n");
+        t("uu", b"begin 644 cat.txt\n#0V%T\n`\n");
+        t("uu", b"begin rw cat.txt\n#0V%T\n`\nend\n");
+        t("xx", b"begin 644 cat\n1Eq3~\n+\nend\n");
+
+        t("yenc", b"\x92\x8f\x96\x96\x99\n=yend size=5\n");
+        t(
+            "yenc",
+            b"=ybegin line=128 size=5 name=a\n\x92\x8f\x96\x96\x99\n=yend size=5 crc32=3610a687\n",
+        );
+        t(
+            "yenc",
+            b"=ybegin line=128 size=5 name=a\n\x92\x8f\x96\x96\n=yend size=5\n",
+        );
+        t(
+            "yenc",
+            b"=ybegin part=1 line=128 size=11 name=hi.txt\n=ypart begin=1 end=5\n\x92\x8f\x96\x96\x99\n=yend size=5 part=1\n",
+        );
+
+        t("qp", b"caf=c3=a9");
+        t("qp", b"100% =XY");
+        t("qp", b"trailing =4");
+        t("qp", b"caf\xc3\xa9");
+
+        t("url", b"100%");
+        t("url", b"100%2");
+        t("url:form", b"%zz");
+
+        t("html", b"&#xD800;");
+        t("html", b"&#1114112;");
+        t("html", b"&#99999999999;");
+
+        t("punycode", b"b!!");
+        t("punycode", "bücher".as_bytes());
+        t("idna", b"a.xn--zz.com");
+
+        t("literal:c", b"abc");
+        t("literal:c", br#""\x100""#);
+        t("literal:c", br#""\q""#);
+        t("literal:rust", br#"b"\x4""#);
+        t("literal:rust", br#"b"abc"#);
+        t("literal:rust", b"b\"abc\\");
+        t("literal:python", br"b'\400'");
+        t("literal:json", br#""\ud83e""#);
+        t("literal:json", br#""\'""#);
+        t("literal:json", b"\"a\nb\"");
+        t("literal:shell", br"'a'");
+
+        t("base45", b"GGW");
+        t("base45", b"ZZZ");
+        t("base45", b"BB8B");
+        t("base45", b"BB8:;");
+        t("base45", b"bb8");
+
+        t("base58", b"0OIl");
+        t("base58:monero", b"1");
+        t("base58:monero", b"zzzzzzzzzzz");
+        t("base58check", b"13vQB7B6MrGQZaxCqW9KEr");
+        t("base58check", b"1111");
+        t("base58check,version=5", b"13vQB7B6MrGQZaxCqW9KER");
+
+        t("bech32", b"A12UEL5l");
+        t("bech32", b"a12uel5m");
+        t("bech32", b"A1LQFN3A");
+        t("bech32m", b"A12UEL5L");
+        t("bech32,hrp=b", b"A12UEL5L");
+        t("bech32", b"pzry9x0s0muk");
+
+        t("utf16le", b"h\x00\xe9");
+        t("utf16le", b"\x00\xd8h\x00");
+        t("ucs2", b"=\xd8\x00\xde");
+
+        let e = decode(&"charset:shift_jis".parse().unwrap(), b"ab\x82").unwrap_err();
+        assert!(e.to_string().contains("at offset 2"), "{}", e);
+        let e = decode(&"charset:utf-8".parse().unwrap(), b"abc\xffd").unwrap_err();
+        assert!(e.to_string().contains("at offset 3"), "{}", e);
+    }
+
+    #[test]
+    fn it_reports_info() {
+        let e = "base58check".parse().unwrap();
+        let (decoded, info) = decode_with_info(&e, "fjQUxjVufxYVKwAWuWBYu").unwrap();
+        assert_eq!(decoded, b"hello world");
+        assert_eq!(
+            info,
+            [Info {
+                encoding: "base58check".into(),
+                name: "version",
+                value: "5".into()
+            }]
+        );
+
+        let e = decode(&e, "13vQB7B6MrGQZaxCqW9KEr").unwrap_err();
+        assert!(e.to_string().contains("bad checksum"), "{}", e);
+
+        let e = decode(&"url".parse().unwrap(), "a%20b%2").unwrap_err();
+        assert!(e.to_string().contains("`%2` at offset 5"), "{}", e);
+
+        let e = decode(&"html".parse().unwrap(), "a &#xdfff b").unwrap_err();
+        assert!(e.to_string().contains("`&#xdfff` at offset 2"), "{}", e);
+
+        let e = decode(&"literal:rust".parse().unwrap(), r#"b"ab" c"#).unwrap_err();
+        assert!(
+            e.to_string()
+                .contains("after the closing quote at offset 6"),
+            "{}",
+            e
+        );
+
+        let e = |inp: &str| {
+            decode(&"idna".parse().unwrap(), inp)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            e("ok.example\nxn--bcher-kva.xn--zz"),
+            "Failed to decode idna: invalid label `xn--zz` on line 2: invalid punycode"
+        );
+        // This is valid punycode, for a character that isn't allowed
+        assert_eq!(
+            e("xn--a.com"),
+            "Failed to decode idna: invalid label `xn--a` on line 1"
+        );
+
+        let e = "bech32m,bits=5".parse().unwrap();
+        let (decoded, info) =
+            decode_with_info(&e, "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx").unwrap();
+        assert_eq!(decoded, (0..32).rev().collect::<Vec<u8>>());
+        assert_eq!(info[0].name, "hrp");
+        assert_eq!(info[0].value, "abcdef");
+
+        let e = "uu".parse().unwrap();
+        let (decoded, info) = decode_with_info(&e, "begin 600 cat.txt\n#0V%T\n`\nend\n").unwrap();
+        assert_eq!(decoded, b"Cat");
+        let info = info
+            .iter()
+            .map(|i| (i.name, i.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(info, [("name", "cat.txt"), ("mode", "600")]);
+        // Line numbers count the preamble before `begin` too
+        let e = decode(
+            &e,
+            "From: a@example.com\nSubject: cat\n\nbegin 644 f\n#0V%T\n#0V{T\n`\nend\n",
+        )
+        .unwrap_err();
+        assert!(
+            e.to_string().contains("invalid character '{' on line 6"),
+            "{}",
+            e
+        );
+
+        let e = "yenc".parse().unwrap();
+        let (_, info) = decode_with_info(
+            &e,
+            "=ybegin line=128 size=0 name=empty file.txt\n=yend size=0 crc32=00000000\n",
+        )
+        .unwrap();
+        assert_eq!(info[0].name, "name");
+        assert_eq!(info[0].value, "empty file.txt");
+    }
 
     #[test]
     fn it_decodes() {
-        let t = |e: Encoding, inp: &[u8], out: &str| {
-            let inp = decode(&e, inp).unwrap();
+        let t = |e: &str, inp: &[u8], out: &str| {
+            let inp = decode(&e.parse().unwrap(), inp).unwrap();
             let inp = std::str::from_utf8(&inp).unwrap();
             assert_eq!(inp, out);
         };
 
         let s = "allyourbasearebelongtous";
-        t(Encoding::ASCII, b"allyourbasearebelongtous", s);
-        t(Encoding::UTF8, b"allyourbasearebelongtous", s);
+        t("ascii", b"allyourbasearebelongtous", s);
+        t("utf8", b"allyourbasearebelongtous", s);
 
         t(
-            Encoding::Base32Crockford,
+            "base32:crockford",
             b"C5P6RYBFENS64RBKCNGQ4SB2CNP6YVK7EHQQAWR",
             s,
         );
+        t("base32", b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y=", s);
+        t("base32|", b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y", s);
+        t("base32:hex", b"C5M6OUBFELP64OBJCLGN4PB2CLM6URJ7EHNNASO=", s);
+        t("base32hex|", b"c5m6oubfelp64obj", "allyourbas");
+        t("zbase32", b"cfsga6mxqi3gramuciozr3mncisg65u8qtzzkha=", s);
+        t("base32:z|", b"cfsga6mxqi3gramuciozr3mncisg65u8qtzzkha", s);
+        t("geohash", b"d5q6sycgfpt64scmdphr4tc2dpq6yvm7fjrrbws=", s);
+        t("geohash|", b"D5Q6SYCGFPT64SCMDPHR4TC2DPQ6YVM7FJRRBWS", s);
+        {
+            let s = "allyourbasearebelongtous!";
+            t("base64:bcrypt", b"WUvqcU7zakHfa0TfakTgXUvtZkbyZ1TxGO", s);
+            t("base64:binhex", b"A9`VG9pdEP*JEe8JEP8KB9`ZDPFcDh8b)3", s);
+            t("base64:crypt", b"MKlgSKxpQa7VQqJVQaJWNKljPaRoPrJn6E", s);
+            t("base64:imap", b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ", s);
+            t("base64", b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==", s);
+            t("base64|", b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ", s);
+            t("base64:url", b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==", s);
+            t("base64:url|", b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ", s);
+        }
+
+        t("base85", b"VQg%9Z*_8FVRL0+a%Ey=Y;SI7bZ>QY", s);
+        t("ascii85", b"<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>", s);
+
         t(
-            Encoding::Base32Rfc4648,
-            b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y=",
+            "hex",
+            b"616c6c796f75726261736561726562656c6f6e67746f7573",
             s,
         );
+
+        t("base36", b"FUVRSIVVNFRBJWAJO", "hello world");
+        t("base36:upper", b"fuvrsivvnfrbjwajo", "hello world");
+        t("base62", b"AAwf93rvy4aWQVw", "hello world");
+        t("base62:lower", b"aaWF93RVY4AwqvW", "hello world");
+        t("base36", b"003J", "\0\0\x7f");
+        t("base62", b"0023", "\0\0\x7f");
+        t("base36", b"3w5e11264sgsg", "\x01\0\0\0\0\0\0\0\0");
+
+        t("base58", b"StV1DL6CwTryKyV", "hello world");
+        t("base58:flickr", b"rTu1dk6cWsRYjYu", "hello world");
+        t("base58:ripple", b"StVrDLaUATiyKyV", "hello world");
+        t("base58:monero", b"JTmsyNwG6XQ1fS9Z", "hello world");
+        t("base58", b"112", "\0\0\x01");
+        t("base58:monero", b"11112", "\0\0\x01");
+        t("base58check", b"13vQB7B6MrGQZaxCqW9KER", "hello world");
         t(
-            Encoding::Base32Rfc4648NoPadding,
-            b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y",
-            s,
+            "base58check,version=5",
+            b"fjQUxjVufxYVKwAWuWBYu",
+            "hello world",
         );
-        {
-            let s = "allyourbasearebelongtous!";
-            t(
-                Encoding::Base64Bcrypt,
-                b"WUvqcU7zakHfa0TfakTgXUvtZkbyZ1TxGO",
-                s,
-            );
-            t(
-                Encoding::Base64Binhex,
-                b"A9`VG9pdEP*JEe8JEP8KB9`ZDPFcDh8b)3",
-                s,
-            );
-            t(
-                Encoding::Base64Crypt,
-                b"MKlgSKxpQa7VQqJVQaJWNKljPaRoPrJn6E",
-                s,
-            );
-            t(
-                Encoding::Base64ImapMutf7,
-                b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
-                s,
-            );
-            t(
-                Encoding::Base64Standard,
-                b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==",
-                s,
-            );
-            t(
-                Encoding::Base64StandardNoPadding,
-                b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
-                s,
-            );
-            t(
-                Encoding::Base64UrlSafe,
-                b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==",
-                s,
-            );
-            t(
-                Encoding::Base64UrlSafeNoPadding,
-                b"YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
-                s,
-            );
-        }
 
+        t("ascii85", b"@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C", s);
+        t("ascii85", b"<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C", s);
         t(
-            Encoding::Base85Rfc1924,
-            b"VQg%9Z*_8FVRL0+a%Ey=Y;SI7bZ>QY",
-            s,
+            "ascii85",
+            b"<~zy\n@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>",
+            &format!("\0\0\0\0    {}", s),
+        );
+        assert_eq!(
+            decode(&"z85".parse().unwrap(), b"HelloWorld").unwrap(),
+            b"\x86\x4f\xd2\x6f\xb5\x59\xf7\x5b"
         );
         t(
-            Encoding::Base85Ascii,
-            b"<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>",
-            s,
+            "btoa",
+            b"xbtoa Begin\nBOu!rD]j7BEbo7d\nxbtoa End N 11 b E 20 S 467 R 339ac\n",
+            "hello world",
         );
 
+        t("base91", b";iDgk>;Nd!Y.$PUo(G^Ih>/pr%O;GF", s);
+        t("base91", b">OwJh>}AQ;r@@Y?F\n", "Hello, World!");
         t(
-            Encoding::Hex,
-            b"616c6c796f75726261736561726562656c6f6e67746f7573",
+            "base122",
+            "0[\u{2c7}K=jr1\x18.6+\x05de1\x19-F{9Nt7].0".as_bytes(),
             s,
         );
+        t("base122", "4\x1a \u{780}".as_bytes(), "hi\0");
+        t("base122", "\x05\u{780}".as_bytes(), "\n");
+        t("base2048", "\u{6c5}\u{514}3".as_bytes(), "hi!");
+        t("base32768", "\u{5a94}\u{29f}".as_bytes(), "hi");
+
+        t("qp", b"caf=C3=A9 =3D good=20\n", "café = good \n");
+        t(
+            "qp",
+            b"soft =\r\nbreak=  \r\nhere\t\r\n",
+            "soft breakhere\r\n",
+        );
+        t("qp", b"lf soft=\nbreak", "lf softbreak");
+        t("qp:lenient", b"100% =3d =XY =\n", "100% = =XY ");
+        t("qp:lenient", b"caf=c3=a9", "café");
+
+        t("url", b"a%20b&c=d/%C3%A9?~*", "a b&c=d/é?~*");
+        t("url:component", b"a+b%2fc%2F", "a+b/c/");
+        t("url:form", b"q=a+b%2Bc", "q=a b+c");
+
+        t(
+            "html",
+            b"&lt;b&gt; &amp;amp; &#39;&#x27;&#X27;",
+            "<b> &amp; '''",
+        );
+        t(
+            "html",
+            b"&eacute;&Eacute;&NotNestedGreaterGreater;&fjlig;",
+            "éÉ⪢̸fj",
+        );
+        // Like browsers, old names don't need a semicolon and unknown names are kept
+        t(
+            "html",
+            b"&copy 2024 &notit; &#169 AT&T &bogus; &#;",
+            "© 2024 ¬it; © AT&T &bogus; &#;",
+        );
+
+        t("punycode", b"bcher-kva\nabc-", "bücher\nabc");
+        t("idna", b"xn--bcher-kva.example", "bücher.example");
+        t("idna", b"XN--80AK6AA92E.com\n\n", "аррӏе.com\n\n");
+
+        t("literal:c", br#""\x41\x000042\101\?\a" "#, "ABA?\x07");
+        t("literal:rust", b"  b\"a\\\n    b\\0\\x7f\"\n", "ab\0\x7f");
+        t("literal:python", br#"B"\q\'\x41\7""#, "\\q'A\x07");
+        t(
+            "literal:python",
+            br"b'\
+x'",
+            "x",
+        );
+        t("literal:json", br#""\ud83e\udd80 \u00e9\/""#, "🦀 é/");
+        t(
+            "literal:shell",
+            br"$'\cA\u00e9\e\x414\0101'",
+            "\x01é\x1bA4\x081",
+        );
+
+        t("uu", b"begin 644 cat.txt\n#0V%T\n`\nend\n", "Cat");
+        t(
+            "uu",
+            b"From: someone\r\n\r\nbegin 600 hello.txt\r\n,:&5L;&\\@=V]R;&0*\r\n \r\nend\r\n",
+            "hello world\n",
+        );
+        // Trailing spaces stripped in transit
+        t("uu", b"begin 644 a\n#86)C\n`\nend\n", "abc");
+        t("uu", b"begin 644 a\n\"86(\n`\nend\n", "ab");
+        t("xx", b"begin 644 cat\n1Eq3o\n+\nend\n", "Cat");
+
+        t(
+            "yenc",
+            b"=ybegin line=4 size=11 name=data\r\n\x92\x8f\x96\x96\r\n\x99J\xa1\x99\r\n\x9c\x96\x8e\r\n=yend size=11 crc32=0d4a1185\r\n",
+            "hello world",
+        );
+        // The parts of a multipart file can be in any order
+        t(
+            "yenc",
+            b"=ybegin part=2 total=2 line=128 size=11 name=hi.txt\n=ypart begin=6 end=11\nJ\xa1\x99\x9c\x96\x8e\n=yend size=6 part=2 pcrc32=4a3b42cb crc32=0d4a1185\n\
+              =ybegin part=1 total=2 line=128 size=11 name=hi.txt\n=ypart begin=1 end=5\n\x92\x8f\x96\x96\x99\n=yend size=5 part=1 pcrc32=3610a686\n",
+            "hello world",
+        );
+        assert_eq!(
+            decode(
+                &"yenc".parse().unwrap(),
+                b"=ybegin line=128 size=6 name=odd file.bin\n=n=@=J=M=}=`\n=yend size=6 crc32=640c5036\n"
+            )
+            .unwrap(),
+            b"\x04\xd6\xe0\xe3\x13\xf6"
+        );
+
+        t("base45", b"BB8", "AB");
+        t("base45", b"%69 VD92EX0", "Hello!!");
+        t("base45", b"QED8WEX0", "ietf!");
+
+        t("bech32", b"A12UEL5L", "");
+        t("bech32m", b"a1lqfn3a", "");
+        t("bech32,hrp=test", b"test1dpjkcmr09ys0qs", "hello");
+
+        t("bin", b"0110000101101100", "al");
+        t("bin", b"0110 0001\n0110_1100", "al");
+        t("bin,order=lsb", b"10000110 00110110", "al");
+        t("oct", b"141154", "al");
+        t("oct", b"141:154", "al");
+
+        t("utf16le", b"h\x00\xe9\x00=\xd8\x00\xde", "hé😀");
+        t("utf16be", b"\x00h\x00\xe9\xd8=\xde\x00", "hé😀");
+        t(
+            "utf16le,errors=replace",
+            b"\x00\xd8h\x00\xe9",
+            "\u{fffd}h\u{fffd}",
+        );
+        t("ucs2", b"h\x00\xe9\x00", "hé");
+        t("latin1", b"h\xe9", "hé");
+        t("charset:shift_jis", b"\x93\xfa\x96{", "日本");
+        t("charset:koi8-r", b"\xd0\xd2\xc9\xd7\xc5\xd4", "привет");
+        t("charset:latin1", b"\x80", "€");
+        t("charset:big5,errors=replace", b"a\xff", "a\u{fffd}");
+
+        t("rot13", b"nyylbheonfrnerorybatgbhf", s);
+        t("rot47", b"2==J@FC32D62C636=@?8E@FD", s);
+        t("rot:3", b"doobrxuedvhduhehorqjwrxv", s);
+        t("caesar:-3", b"xiivlroyxpbxobybilkdqlrp", s);
+        t("rot:0", s.as_bytes(), s);
     }
 }
@@ -0,0 +1,308 @@
+//! Guess which encoding some data is in.
+
+use std::str;
+
+use crate::codec::{registry, Codec};
+use crate::Encoding;
+
+/// The score given to encodings that can output any byte, like `raw`.
+///
+/// The input is always valid for these, so they're only chosen when nothing else fits well.
+const FALLBACK_SCORE: f64 = 0.2;
+
+/// The evidence needed before a match is more than a coincidence.
+///
+/// With this many encodings to try, some short inputs will decode into something that looks
+/// plausible by chance.
+const PRIOR_BITS: f64 = 8.0;
+
+/// How surprising it is for a random byte to be readable: there are 98 printable or whitespace
+/// ASCII characters.
+const TEXT_BITS: f64 = 1.385; // log2(256 / 98)
+
+/// The longest input that encodings written as one big number, like base36, are tried on.
+///
+/// Converting them takes time quadratic in the length, which would stall detection of large
+/// inputs.
+const MAX_BIG_NUMBER_LEN: usize = 4096;
+
+/// A possible encoding of some data, and how likely it is to be the right one.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub encoding: Encoding,
+    /// The data, decoded from this encoding.
+    pub decoded: Vec<u8>,
+    /// The overall score of this candidate, from 0 to 1.
+    pub score: f64,
+    /// How strongly the characters in the input point to this encoding's alphabet, from 0 to 1.
+    ///
+    /// The longer the input and the smaller the alphabet, the less likely a match is a coincidence.
+    pub alphabet_fit: f64,
+    /// Whether encoding the output again gives back the input, i.e. it's padded correctly and
+    /// doesn't have any stray bits.
+    pub valid_padding: bool,
+    /// How much the decoded output looks like text, from 0 to 1.
+    pub plausibility: f64,
+}
+
+/// Every encoding that `data` can be decoded from, best match first.
+///
+/// Surrounding whitespace is ignored. Data that doesn't look like it's in any particular encoding
+/// ranks `raw` first.
+pub fn candidates(data: &[u8]) -> Vec<Candidate> {
+    let data = trim(data);
+    let mut candidates = registry()
+        .iter()
+        .filter_map(|codec| candidate(*codec, data))
+        .collect::<Vec<_>>();
+
+    // This is a stable sort, so ties are broken by the order of the registry
+    candidates.sort_by(|a, b| b.score.total_cmp(&a.score));
+    candidates
+}
+
+/// The most likely encoding of `data`, see `candidates`.
+pub fn detect(data: &[u8]) -> Option<Candidate> {
+    candidates(data).into_iter().next()
+}
+
+/// The most layers `peel` will remove, in case some encoding decodes into itself.
+const MAX_LAYERS: usize = 64;
+
+/// The score a layer needs before `peel` decodes it.
+///
+/// This is well above `FALLBACK_SCORE`, since short text like `QWERTY` or `1234` fits some
+/// alphabets well enough to beat `raw`, but shouldn't be decoded unless asked.
+const PEEL_THRESHOLD: f64 = 0.7;
+
+/// Repeatedly detect the encoding of `data` and decode it, until it no longer looks like it's in
+/// any encoding.
+///
+/// Returns the encodings that were decoded, outermost first, and the data that's left.
+pub fn peel(data: &[u8]) -> (Vec<Encoding>, Vec<u8>) {
+    let mut layers = vec![];
+    let mut data = data.to_owned();
+    while layers.len() < MAX_LAYERS {
+        match detect(&data) {
+            Some(candidate) if candidate.score > PEEL_THRESHOLD && candidate.decoded != data => {
+                layers.push(candidate.encoding);
+                data = candidate.decoded;
+            }
+            _ => break,
+        }
+    }
+
+    (layers, data)
+}
+
+fn candidate(codec: &'static dyn Codec, data: &[u8]) -> Option<Candidate> {
+    if codec.is_big_number() && data.len() > MAX_BIG_NUMBER_LEN {
+        return None;
+    }
+
+    let alphabet = alphabet(codec)?;
+    let decoded = codec.decode(data).ok()?;
+    let valid_padding = match codec.encode(&decoded) {
+        Ok(encoded) => encoded.eq_ignore_ascii_case(data),
+        Err(_) => false,
+    };
+
+    let (readable, plausibility) = readable(&decoded);
+    // Input that decodes to itself, like plain text as `url`, is no evidence for the encoding
+    let fallback = alphabet.iter().all(|b| *b) || decoded == data;
+    let alphabet_bits = if fallback {
+        0.0
+    } else {
+        alphabet_bits(&alphabet, data)
+    };
+    let score = if fallback {
+        FALLBACK_SCORE
+    } else {
+        // Decoding arbitrary text gives arbitrary bytes, so readable output is evidence too
+        let text_bits = readable as f64 * TEXT_BITS * plausibility;
+        let padding = if valid_padding { 1.0 } else { 0.5 };
+        confidence(alphabet_bits + text_bits - PRIOR_BITS) * (0.5 + 0.5 * plausibility) * padding
+    };
+
+    Some(Candidate {
+        encoding: codec.into(),
+        decoded,
+        score,
+        alphabet_fit: confidence(alphabet_bits),
+        valid_padding,
+        plausibility,
+    })
+}
+
+/// Turn bits of evidence into a confidence from 0 to 1.
+fn confidence(bits: f64) -> f64 {
+    1.0 - (-bits.max(0.0) / 8.0).exp2()
+}
+
+/// The set of bytes an encoding outputs, found by encoding every byte value.
+///
+/// Returns `None` for encodings that can't encode arbitrary data or need an argument, since
+/// they're not suitable for detection.
+fn alphabet(codec: &dyn Codec) -> Option<[bool; 256]> {
+    if codec.argument().is_some() {
+        return None;
+    }
+
+    // The run of zeros covers shorthands like ascii85's `z`
+    let sample = (0..=255)
+        .chain([0; 8])
+        .chain((0..=255).rev())
+        .collect::<Vec<u8>>();
+
+    let mut alphabet = [false; 256];
+    for b in codec.encode(&sample).ok()? {
+        alphabet[b as usize] = true;
+    }
+
+    Some(alphabet)
+}
+
+/// How much evidence the characters in `data` give for it being in `alphabet` rather than text.
+///
+/// Encoded data uses every character of its alphabet equally, whereas text is mostly lowercase
+/// letters. Characters that are only in the alphabet in the other case (for decoders that ignore
+/// case) are unusual for encoded data, and count against it.
+fn alphabet_bits(alphabet: &[bool; 256], data: &[u8]) -> f64 {
+    let size = alphabet.iter().filter(|b| **b).count() as f64;
+    let in_alphabet = |b: u8| alphabet[b as usize];
+    data.iter()
+        .map(|&b| {
+            let p = if in_alphabet(b) {
+                1.0 / size
+            } else if in_alphabet(b.to_ascii_lowercase()) || in_alphabet(b.to_ascii_uppercase()) {
+                0.1 / size
+            } else {
+                return 0.0;
+            };
+
+            (p / text_probability(b)).log2()
+        })
+        .sum()
+}
+
+/// Roughly how likely a byte is to appear in English text.
+fn text_probability(b: u8) -> f64 {
+    match b {
+        b'a'..=b'z' => 0.70 / 26.0,
+        b'A'..=b'Z' => 0.08 / 26.0,
+        b'0'..=b'9' => 0.05 / 10.0,
+        b' ' => 0.12,
+        // The remaining 32 printable characters are punctuation
+        b'!'..=b'~' => 0.04 / 32.0,
+        b'\t' | b'\n' | b'\r' => 0.01 / 3.0,
+        _ => 0.0001,
+    }
+}
+
+/// The number of readable characters in `data`, and the fraction of it that they make up.
+fn readable(data: &[u8]) -> (usize, f64) {
+    let readable = |c: char| !c.is_control() || c.is_ascii_whitespace();
+    let (readable, total) = match str::from_utf8(data) {
+        Ok(s) => (
+            s.chars().filter(|c| readable(*c)).count(),
+            s.chars().count(),
+        ),
+        Err(_) => (
+            data.iter()
+                .filter(|b| b.is_ascii() && readable(**b as char))
+                .count(),
+            data.len(),
+        ),
+    };
+
+    match total {
+        0 => (0, 0.0),
+        _ => (readable, readable as f64 / total as f64),
+    }
+}
+
+fn trim(data: &[u8]) -> &[u8] {
+    let start = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    let end = data
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+
+    &data[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::detect::{detect, peel};
+    use crate::{encode, Encoding};
+
+    #[test]
+    fn it_detects() {
+        let t = |inp: &[u8], enc: &str, out: &[u8]| {
+            let candidate = detect(inp).unwrap();
+            assert_eq!(candidate.encoding.to_string(), enc);
+            assert_eq!(candidate.decoded, out);
+        };
+
+        let s = b"allyourbasearebelongtous";
+        t(b"allyourbasearebelongtous", "raw", s);
+        t(
+            b"all your base are belong to us\n",
+            "raw",
+            b"all your base are belong to us",
+        );
+        t(
+            b"616c6c796f75726261736561726562656c6f6e67746f7573",
+            "hex",
+            s,
+        );
+        t(
+            b"616C6C796F75726261736561726562656C6F6E67746F7573",
+            "hex",
+            s,
+        );
+        t(b"aGVsbG8=", "base64", b"hello");
+        t(b"hello", "raw", b"hello");
+        t(b"Password", "raw", b"Password");
+        t(b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y=", "base32", s);
+        t(b"MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y", "base32|", s);
+        t(b"YWxseW91cmJhc2VhcmViZWxvbmd0b3Vz\n", "base64|", s);
+        t(b"<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>", "ascii85", s);
+        t(b"c0ffee00", "hex", &[0xc0, 0xff, 0xee, 0x00]);
+        t(
+            b"caf%C3%A9%20au%20lait",
+            "url:component",
+            "café au lait".as_bytes(),
+        );
+
+        // Long inputs aren't decoded as one big number, which would be slow
+        for enc in ["base36", "base58", "base58check"] {
+            let long = encode(&enc.parse().unwrap(), [b'a'; 4096]).unwrap();
+            assert_ne!(detect(&long).unwrap().encoding.to_string(), enc);
+        }
+    }
+
+    #[test]
+    fn it_peels() {
+        let s = b"all your base are belong to us";
+        let layers = ["base64", "hex", "base32", "ascii85"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect::<Vec<Encoding>>();
+        let data = layers
+            .iter()
+            .rev()
+            .fold(s.to_vec(), |data, enc| encode(enc, data).unwrap());
+
+        assert_eq!(peel(&data), (layers, s.to_vec()));
+        assert_eq!(peel(s), (vec![], s.to_vec()));
+
+        // Text that happens to fit an alphabet is left alone
+        for s in ["QWERTY", "1234", "abc123"] {
+            assert_eq!(peel(s.as_bytes()), (vec![], s.as_bytes().to_vec()));
+        }
+    }
+}
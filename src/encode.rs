@@ -1,137 +1,305 @@
-use anyhow::{bail, Result};
+use crate::{Encoding, Result};
 
-use crate::cli::Encoding;
-
-pub fn encode(enc: &Encoding, data: Vec<u8>) -> Result<Vec<u8>> {
-    Ok(match enc {
-        Encoding::Raw => data,
-        Encoding::ASCII => {
-            let s = String::from_utf8(data)?;
-            if !s.is_ascii() {
-                bail!("Data contains non-ASCII bytes, and therefore cannot be encoded as ASCII");
-            }
-
-            s.into_bytes()
-        }
-        Encoding::Hex => hex::encode(&data).into_bytes(),
-
-        Encoding::UTF8 => String::from_utf8(data)?.into_bytes(),
-        Encoding::Base32Crockford => {
-            base32::encode(base32::Alphabet::Crockford, &data).into_bytes()
-        }
-        Encoding::Base32Rfc4648 => {
-            base32::encode(base32::Alphabet::RFC4648 { padding: true }, &data).into_bytes()
-        }
-        Encoding::Base32Rfc4648NoPadding => {
-            base32::encode(base32::Alphabet::RFC4648 { padding: false }, &data).into_bytes()
-        }
-
-        Encoding::Base64Bcrypt => base64::encode_config(&data, base64::BCRYPT).into_bytes(),
-        Encoding::Base64Binhex => base64::encode_config(&data, base64::BINHEX).into_bytes(),
-        Encoding::Base64Crypt => base64::encode_config(&data, base64::CRYPT).into_bytes(),
-        Encoding::Base64ImapMutf7 => base64::encode_config(&data, base64::IMAP_MUTF7).into_bytes(),
-        Encoding::Base64Standard => base64::encode_config(&data, base64::STANDARD).into_bytes(),
-        Encoding::Base64StandardNoPadding => {
-            base64::encode_config(&data, base64::STANDARD_NO_PAD).into_bytes()
-        }
-        Encoding::Base64UrlSafe => base64::encode_config(&data, base64::URL_SAFE).into_bytes(),
-        Encoding::Base64UrlSafeNoPadding => {
-            base64::encode_config(&data, base64::URL_SAFE_NO_PAD).into_bytes()
-        }
-
-        Encoding::Base85Rfc1924 => base85::encode(&data).into_bytes(),
-        Encoding::Base85Ascii => ascii85::encode(&data).into_bytes(),
-    })
+pub fn encode(enc: &Encoding, data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    enc.codec().encode(data.as_ref())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{cli::Encoding, encode::encode};
+    use crate::encode::encode;
 
     #[test]
     fn it_works() {
-        let t = |e: Encoding, inp: &[u8], out: &str| {
-            let inp = encode(&e, inp.into()).unwrap();
+        let t = |e: &str, inp: &[u8], out: &str| {
+            let inp = encode(&e.parse().unwrap(), inp).unwrap();
             let inp = std::str::from_utf8(&inp).unwrap();
             assert_eq!(inp, out);
         };
 
         let s = "allyourbasearebelongtous".as_bytes();
-        t(Encoding::ASCII, s, "allyourbasearebelongtous");
-        t(Encoding::UTF8, s, "allyourbasearebelongtous");
+        t("ascii", s, "allyourbasearebelongtous");
+        t("utf8", s, "allyourbasearebelongtous");
 
         t(
-            Encoding::Base32Crockford,
+            "base32:crockford",
             s,
             "C5P6RYBFENS64RBKCNGQ4SB2CNP6YVK7EHQQAWR",
         );
-        t(
-            Encoding::Base32Rfc4648,
-            s,
-            "MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y=",
-        );
-        t(
-            Encoding::Base32Rfc4648NoPadding,
-            s,
-            "MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y",
-        );
+        t("base32", s, "MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y=");
+        t("base32|", s, "MFWGY6LPOVZGEYLTMVQXEZLCMVWG63THORXXK4Y");
+        t("base32:hex", s, "C5M6OUBFELP64OBJCLGN4PB2CLM6URJ7EHNNASO=");
+        t("base32hex|", s, "C5M6OUBFELP64OBJCLGN4PB2CLM6URJ7EHNNASO");
+        t("zbase32", s, "cfsga6mxqi3gramuciozr3mncisg65u8qtzzkha=");
+        t("base32:z|", s, "cfsga6mxqi3gramuciozr3mncisg65u8qtzzkha");
+        t("geohash", s, "d5q6sycgfpt64scmdphr4tc2dpq6yvm7fjrrbws=");
+        t("geohash|", s, "d5q6sycgfpt64scmdphr4tc2dpq6yvm7fjrrbws");
+        t("base32:hex", b"hi", "D1KG====");
+        t("base32:hex|", b"hi", "D1KG");
 
         {
             // To get padding, the input can't be a multiple of 3
             let s = "allyourbasearebelongtous!".as_bytes();
+            t("base64:bcrypt", s, "WUvqcU7zakHfa0TfakTgXUvtZkbyZ1TxGO");
+            t("base64:binhex", s, "A9`VG9pdEP*JEe8JEP8KB9`ZDPFcDh8b)3");
+            t("base64:crypt", s, "MKlgSKxpQa7VQqJVQaJWNKljPaRoPrJn6E");
+            t("base64:imap", s, "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ");
+            t("base64", s, "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==");
+            t("base64|", s, "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ");
+            t("base64:url", s, "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==");
+            t("base64:url|", s, "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ");
+        }
+
+        t("base85", s, "VQg%9Z*_8FVRL0+a%Ey=Y;SI7bZ>QY");
+        t("ascii85", s, "<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>");
+
+        t("hex", s, "616c6c796f75726261736561726562656c6f6e67746f7573");
+
+        {
+            let s = "hello world".as_bytes();
+            t("base58", s, "StV1DL6CwTryKyV");
+            t("base58:flickr", s, "rTu1dk6cWsRYjYu");
+            t("base58:ripple", s, "StVrDLaUATiyKyV");
+            t("base58:monero", s, "JTmsyNwG6XQ1fS9Z");
+
+            // Leading zeros
+            t("base58", b"\0\0\x01", "112");
+            t("base58:ripple", b"\0\0\x01", "rrp");
+            t("base58:monero", b"\0\0\x01", "11112");
+
+            t("base36", s, "fuvrsivvnfrbjwajo");
+            t("base36:upper", s, "FUVRSIVVNFRBJWAJO");
+            t("base62", s, "AAwf93rvy4aWQVw");
+            t("base62:lower", s, "aaWF93RVY4AwqvW");
+            t("base36", b"\0\0\xff", "0073");
+            t("base62", b"\0\0\xff", "0047");
+            // 2^64, which takes more than one limb
+            t("base36", b"\x01\0\0\0\0\0\0\0\0", "3w5e11264sgsg");
+
             t(
-                Encoding::Base64Bcrypt,
-                s,
-                "WUvqcU7zakHfa0TfakTgXUvtZkbyZ1TxGO",
-            );
-            t(
-                Encoding::Base64Binhex,
-                s,
-                "A9`VG9pdEP*JEe8JEP8KB9`ZDPFcDh8b)3",
-            );
-            t(
-                Encoding::Base64Crypt,
-                s,
-                "MKlgSKxpQa7VQqJVQaJWNKljPaRoPrJn6E",
-            );
-            t(
-                Encoding::Base64ImapMutf7,
-                s,
-                "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
-            );
-            t(
-                Encoding::Base64Standard,
-                s,
-                "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==",
-            );
-            t(
-                Encoding::Base64StandardNoPadding,
-                s,
-                "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
-            );
-            t(
-                Encoding::Base64UrlSafe,
-                s,
-                "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ==",
-            );
-            t(
-                Encoding::Base64UrlSafeNoPadding,
+                "btoa",
                 s,
-                "YWxseW91cmJhc2VhcmViZWxvbmd0b3VzIQ",
+                "xbtoa Begin\nBOu!rD]j7BEbo7d\nxbtoa End N 11 b E 20 S 467 R 339ac\n",
             );
+
+            t("base58check", s, "13vQB7B6MrGQZaxCqW9KER");
+            t("base58check,version=5", s, "fjQUxjVufxYVKwAWuWBYu");
         }
 
-        t(Encoding::Base85Rfc1924, s, "VQg%9Z*_8FVRL0+a%Ey=Y;SI7bZ>QY");
+        t("z85", b"\x86\x4f\xd2\x6f\xb5\x59\xf7\x5b", "HelloWorld");
+        t("base91", s, ";iDgk>;Nd!Y.$PUo(G^Ih>/pr%O;GF");
+        t("base91", b"test", "fPNKd");
+        t("base122", s, "0[\u{2c7}K=jr1\x18.6+\x05de1\x19-F{9Nt7].0");
+        t("base122", b"hi\0", "4\x1a \u{780}");
+        t("base122", b"\n", "\x05\u{780}");
         t(
-            Encoding::Base85Ascii,
+            "base2048",
             s,
-            "<~@;Kb*Dfp)0@<6!gEb/]kCi=3(FDl;C~>",
+            "\u{68b}\u{69b}\u{28c}\u{1008}\u{5d9}\u{41d}\u{8a2}\u{71e}\u{68b}\u{993}\u{5e4}\u{db4}\u{d91}\u{c8b}\u{a74}\u{92b}\u{781}\u{a97}",
+        );
+        t("qp", "café = good \n".as_bytes(), "caf=C3=A9 =3D good=20\n");
+        t("qp", b"tab\t\r\nend ", "tab=09\r\nend=20");
+        t("qp", b"a\rb", "a=0Db");
+        t(
+            "qp",
+            &[b'a'; 80],
+            &format!("{}=\r\n{}", "a".repeat(75), "a".repeat(5)),
+        );
+        t("qp", &[b'a'; 76], &"a".repeat(76));
+        t(
+            "qp",
+            &[&[b'a'; 80][..], b"\nb\n"].concat(),
+            &format!("{}=\n{}\nb\n", "a".repeat(75), "a".repeat(5)),
+        );
+        t(
+            "qp",
+            &[&[b'a'; 80][..], b"\r\n"].concat(),
+            &format!("{}=\r\n{}\r\n", "a".repeat(75), "a".repeat(5)),
         );
+        t(
+            "qp",
+            &[&[b'x'; 74][..], b"\xffy"].concat(),
+            &format!("{}=\r\n=FFy", "x".repeat(74)),
+        );
+
+        let q = "a b&c=d/é?~*".as_bytes();
+        t("url", q, "a%20b&c=d/%C3%A9?~*");
+        t("url:component", q, "a%20b%26c%3Dd%2F%C3%A9%3F~%2A");
+        t("url:form", q, "a+b%26c%3Dd%2F%C3%A9%3F%7E*");
+        t("url:component,safe=/=", q, "a%20b%26c=d/%C3%A9%3F~%2A");
+        t("url:form,safe=~", q, "a+b%26c%3Dd%2F%C3%A9%3F~*");
+        t("url", b":/?#[]@!$&'()*+,;=", ":/?#[]@!$&'()*+,;=");
+        t(
+            "url:component",
+            b":/?#[]@!$&'()*+,;=",
+            "%3A%2F%3F%23%5B%5D%40%21%24%26%27%28%29%2A%2B%2C%3B%3D",
+        );
+
+        let q = "<a href=\"?q=1&r='é'\">🦀</a>".as_bytes();
+        t(
+            "html",
+            q,
+            "&lt;a href=&quot;?q=1&amp;r=&#39;é&#39;&quot;&gt;🦀&lt;/a&gt;",
+        );
+        t(
+            "html:ascii",
+            q,
+            "&lt;a href=&quot;?q=1&amp;r=&#39;&#233;&#39;&quot;&gt;&#129408;&lt;/a&gt;",
+        );
+        t("html:all", b"a<\n", "&#97;&#60;&#10;");
 
+        t("punycode", "bücher".as_bytes(), "bcher-kva");
+        t("punycode", "München\nabc".as_bytes(), "Mnchen-3ya\nabc-");
+        t("idna", "bücher.example".as_bytes(), "xn--bcher-kva.example");
         t(
-            Encoding::Hex,
+            "idna",
+            "Bücher.EXAMPLE\r\nаррӏе.com\n".as_bytes(),
+            "xn--bcher-kva.example\r\nxn--80ak6aa92e.com\n",
+        );
+
+        let q = "a\0b\"'\\\n\x01Fé".as_bytes();
+        t("literal:c", q, r#""a\x00\x62\"'\\\n\x01\x46\xc3\xa9""#);
+        t("literal:rust", q, r#"b"a\0b\"'\\\n\x01F\xc3\xa9""#);
+        t("literal:python", q, r#"b'a\x00b"\'\\\n\x01F\xc3\xa9'"#);
+        t("literal:json", q, r#""a\u0000b\"'\\\n\u0001Fé""#);
+        t(
+            "literal:shell",
+            "a\x1bb\"'\\\n\x01Fé".as_bytes(),
+            r#"$'a\x1bb"\'\\\n\x01F\xc3\xa9'"#,
+        );
+        t("literal:c", b"", "\"\"");
+
+        t("uu", b"Cat", "begin 644 data\n#0V%T\n`\nend\n");
+        t(
+            "uu,name=hello.txt,mode=600",
+            b"hello world\n",
+            "begin 600 hello.txt\n,:&5L;&\\@=V]R;&0*\n`\nend\n",
+        );
+        t("xx,name=cat", b"Cat", "begin 644 cat\n1Eq3o\n+\nend\n");
+        t("base2048", b"hi", "\u{6c5}\u{54c}");
+        t("base2048", b"hi!", "\u{6c5}\u{514}3");
+        t(
+            "base32768",
             s,
-            "616c6c796f75726261736561726562656c6f6e67746f7573",
+            "\u{5716}\u{415e}\u{544e}\u{7d86}\u{394b}\u{73f5}\u{6944}\u{8bc2}\u{5916}\u{421b}\u{734e}\u{6d57}\u{51ff}",
+        );
+        t("base32768", b"hi", "\u{5a94}\u{29f}");
+
+        t("base45", b"AB", "BB8");
+        t("base45", b"Hello!!", "%69 VD92EX0");
+        t("base45", b"base-45", "UJCLQE7W581");
+
+        t("bech32,hrp=a", b"", "a12uel5l");
+        t("bech32m,hrp=A", b"", "a1lqfn3a");
+        t("bech32,hrp=test", b"hello", "test1dpjkcmr09ys0qs");
+        t(
+            "bech32,hrp=abcdef,bits=5",
+            &(0..32).collect::<Vec<u8>>(),
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+        );
+        t(
+            "bech32m,hrp=abcdef,bits=5",
+            &(0..32).rev().collect::<Vec<u8>>(),
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+        );
+
+        t("bin", b"al", "0110000101101100");
+        t("bin,group=4", b"al", "0110 0001 0110 1100");
+        t("bin,group=8,order=lsb", b"al", "10000110 00110110");
+        t("oct", b"al\xff", "141154377");
+        t("oct,group=3", b"al\xff", "141 154 377");
+
+        t("rot13", s, "nyylbheonfrnerorybatgbhf");
+        t("rot47", s, "2==J@FC32D62C636=@?8E@FD");
+        t("rot:3", s, "doobrxuedvhduhehorqjwrxv");
+        {
+            // Only letters are rotated, and only printable ASCII for rot47
+            let s = "Hello, World! é".as_bytes();
+            t("rot13", s, "Uryyb, Jbeyq! é");
+            t("rot47", s, "w6==@[ (@C=5P é");
+            t("rot:3", s, "Khoor, Zruog! é");
+            t("rot:-3", s, "Ebiil, Tloia! é");
+            t("rot:29", s, "Khoor, Zruog! é");
+        }
+    }
+
+    #[test]
+    fn it_encodes_text() {
+        let t = |e: &str, inp: &str, out: &[u8]| {
+            assert_eq!(encode(&e.parse().unwrap(), inp).unwrap(), out);
+        };
+
+        let s = "hé😀";
+        t("utf16le", s, b"h\x00\xe9\x00=\xd8\x00\xde");
+        t("utf16be", s, b"\x00h\x00\xe9\xd8=\xde\x00");
+        t("ucs2,errors=replace", s, b"h\x00\xe9\x00?\x00");
+        t("latin1,errors=replace", s, b"h\xe9?");
+        t("latin1,errors=escape", s, b"h\xe9&#128512;");
+
+        t("charset:shift_jis", "日本", b"\x93\xfa\x96{");
+        t("charset:koi8-r", "привет", b"\xd0\xd2\xc9\xd7\xc5\xd4");
+        t("charset:windows-1252", "€", b"\x80");
+        t("charset:gb18030", "中文", b"\xd6\xd0\xce\xc4");
+        t("charset:euc-kr", "한국", b"\xc7\xd1\xb1\xb9");
+        t("charset:utf-16le", "h", b"h\x00");
+        t(
+            "charset:shift_jis,errors=escape",
+            "日😀",
+            b"\x93\xfa&#128512;",
+        );
+        t("charset:shift_jis,errors=replace", "日😀", b"\x93\xfa?");
+
+        let e = encode(&"charset:shift_jis".parse().unwrap(), "日😀").unwrap_err();
+        assert!(e.to_string().contains("at offset 3"), "{}", e);
+
+        let t = |e: &str, inp: &[u8]| assert!(encode(&e.parse().unwrap(), inp).is_err());
+        t("z85", b"hello");
+        t("ucs2", s.as_bytes());
+        t("latin1", s.as_bytes());
+        t("utf16le", b"\xff");
+        t("html:ascii", b"\xff");
+        t("punycode", b"\xff");
+        t("idna", "abא.com".as_bytes());
+        t("literal:json", b"\xff");
+        t("literal:shell", b"a\0b");
+        t("charset:iso-2022-kr", b"a");
+
+        let e = |inp: &str| {
+            encode(&"idna".parse().unwrap(), inp)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            e("a\u{200e}b.example"),
+            "Failed to encode idna: invalid label `a\u{200e}b` on line 1"
+        );
+        // A leading combining mark, which isn't a disallowed character
+        assert_eq!(
+            e("ok.com\n\u{301}x.com"),
+            "Failed to encode idna: invalid label `\u{301}x` on line 2"
+        );
+    }
+
+    #[test]
+    fn it_encodes_binary() {
+        let t = |e: &str, inp: &[u8], out: &[u8]| {
+            assert_eq!(encode(&e.parse().unwrap(), inp).unwrap(), out);
+        };
+
+        t(
+            "yenc",
+            b"hello",
+            b"=ybegin line=128 size=5 name=data\n\x92\x8f\x96\x96\x99\n=yend size=5 crc32=3610a686\n",
+        );
+        // Critical characters, and a `.` at the start and a space at the end of the line
+        t(
+            "yenc,name=odd file.bin",
+            b"\x04\xd6\xe0\xe3\x13\xf6",
+            b"=ybegin line=128 size=6 name=odd file.bin\n=n=@=J=M=}=`\n=yend size=6 crc32=640c5036\n",
+        );
+        t(
+            "yenc,line=4",
+            b"hello world",
+            b"=ybegin line=4 size=11 name=data\n\x92\x8f\x96\x96\n\x99J\xa1\x99\n\x9c\x96\x8e\n=yend size=11 crc32=0d4a1185\n",
         );
     }
 }
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::codec::{self, Codec, Options};
+use crate::error::Error;
+
+/// An encoding selected from the codec registry, possibly configured with an argument like
+/// `rot:13`.
+#[derive(Clone)]
+pub struct Encoding {
+    codec: Source,
+    name: String,
+}
+
+#[derive(Clone)]
+enum Source {
+    Registry(&'static dyn Codec),
+    Configured(Arc<dyn Codec>),
+}
+
+impl Encoding {
+    pub fn codec(&self) -> &dyn Codec {
+        match &self.codec {
+            Source::Registry(codec) => *codec,
+            Source::Configured(codec) => codec.as_ref(),
+        }
+    }
+
+    /// This encoding with the option `key` set to `value`, unless it was already given or the
+    /// encoding doesn't accept it, e.g. to name the file that `uu` encodes.
+    pub fn with_default_option(&self, key: &str, value: &str) -> Result<Self, Error> {
+        let accepted = self
+            .codec()
+            .options()
+            .iter()
+            .any(|(usage, _)| usage.split('=').next() == Some(key));
+        let given = match self.name.split_once(',') {
+            Some((_, options)) => options.split(',').any(|o| o.split('=').next() == Some(key)),
+            None => false,
+        };
+
+        // Options are separated by commas, so values can't contain them
+        if !accepted || given || value.contains(',') {
+            return Ok(self.clone());
+        }
+
+        format!("{},{}={}", self.name, key, value).parse()
+    }
+}
+
+impl From<&'static dyn Codec> for Encoding {
+    fn from(codec: &'static dyn Codec) -> Self {
+        Self {
+            codec: Source::Registry(codec),
+            name: codec.name().into(),
+        }
+    }
+}
+
+impl fmt::Debug for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Encoding").field(&self.name).finish()
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl PartialEq for Encoding {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Encoding {}
+
+impl FromStr for Encoding {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (spec, options) = match s.split_once(',') {
+            Some((spec, options)) => (spec, Some(options)),
+            None => (s, None),
+        };
+
+        // Either a plain name, or the name of an encoding that takes an argument, like `rot:13`
+        let (codec, arg) = match codec::lookup(spec) {
+            Some(codec) => (codec, None),
+            None => match spec.rsplit_once(':') {
+                Some((name, arg)) => match codec::lookup(name).filter(|c| c.argument().is_some()) {
+                    Some(codec) => (codec, Some(arg)),
+                    None => return Err(Error::UnknownEncoding(s.into())),
+                },
+                None => return Err(Error::UnknownEncoding(s.into())),
+            },
+        };
+
+        if let (Some(usage), None) = (codec.argument(), arg) {
+            return Err(Error::argument(
+                codec.name(),
+                format!("expected `{}:{}`", codec.name(), usage),
+            ));
+        }
+
+        let parsed = match options {
+            Some(options) => Options::parse(codec, options)?,
+            None => Options::default(),
+        };
+        if arg.is_none() && parsed.is_empty() {
+            return Ok(codec.into());
+        }
+
+        let mut name = codec.name().to_string();
+        if let Some(arg) = arg {
+            name.push(':');
+            name.push_str(arg);
+        }
+        if let Some(options) = options {
+            name.push(',');
+            name.push_str(options);
+        }
+
+        Ok(Self {
+            codec: Source::Configured(codec.configure(arg, &parsed)?.into()),
+            name,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::codec::registry;
+    use crate::Encoding;
+
+    #[test]
+    fn serde() {
+        let t = |e: Encoding| assert_eq!(e, Encoding::from_str(&e.to_string()).unwrap());
+
+        // Test all default names
+        for codec in registry() {
+            match codec.argument() {
+                Some(_) => assert!(Encoding::from_str(codec.name()).is_err()),
+                None => t(Encoding::from_str(codec.name()).unwrap()),
+            }
+        }
+
+        // Test arguments
+        t(Encoding::from_str("rot:5").unwrap());
+        assert!(Encoding::from_str("rot:five").is_err());
+        assert!(Encoding::from_str("hex:5").is_err());
+        t(Encoding::from_str("charset:shift_jis").unwrap());
+        assert!(Encoding::from_str("charset:klingon").is_err());
+
+        // Test options
+        t(Encoding::from_str("bin,group=4,order=lsb").unwrap());
+        assert!(Encoding::from_str("bin,group=four").is_err());
+        assert!(Encoding::from_str("bin,order=backwards").is_err());
+        assert!(Encoding::from_str("bin,size=4").is_err());
+        assert!(Encoding::from_str("hex,group=4").is_err());
+        t(Encoding::from_str("url:component,safe=/").unwrap());
+        assert!(Encoding::from_str("url,safe=%").is_err());
+        assert!(Encoding::from_str("url:form,safe=+").is_err());
+
+        // Test short names
+        let t = |name, s| assert_eq!(name, Encoding::from_str(s).unwrap().to_string());
+        t("base32", "base32:rfc4648");
+        t("base32|", "base32:rfc4648|");
+        t("base64", "base64:standard");
+        t("base64|", "base64:standard|");
+        t("rot:3", "caesar:3");
+    }
+
+    #[test]
+    fn default_options() {
+        let t = |e: &str, name: &str| {
+            let e = Encoding::from_str(e).unwrap();
+            let e = e.with_default_option("name", "notes.txt").unwrap();
+            assert_eq!(e.to_string(), name);
+        };
+
+        t("uu", "uu,name=notes.txt");
+        t("xx,mode=600", "xx,mode=600,name=notes.txt");
+        t("uu,name=other.txt", "uu,name=other.txt");
+        t("hex", "hex");
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt::Display;
+use std::io;
+
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The errors that encoding and decoding can fail with.
+///
+/// More variants may be added, so matches on this need a wildcard arm.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// No registered encoding has the given name.
+    #[error("Unknown encoding: {0}")]
+    UnknownEncoding(String),
+
+    /// The argument given with an encoding's name, like the `N` in `rot:N`, was invalid.
+    #[error("Invalid argument for {encoding}: {message}")]
+    Argument {
+        encoding: &'static str,
+        message: String,
+    },
+
+    /// The data could not be represented in the requested encoding.
+    #[error("Failed to encode {encoding}: {message}")]
+    Encode {
+        encoding: &'static str,
+        message: String,
+    },
+
+    /// The input was not valid for the encoding it was decoded from.
+    #[error("Failed to decode {encoding}: {message}")]
+    Decode {
+        encoding: &'static str,
+        message: String,
+    },
+
+    /// Reading input or writing output failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl Error {
+    pub fn argument(encoding: &'static str, message: impl Display) -> Self {
+        Self::Argument {
+            encoding,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn encode(encoding: &'static str, message: impl Display) -> Self {
+        Self::Encode {
+            encoding,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn decode(encoding: &'static str, message: impl Display) -> Self {
+        Self::Decode {
+            encoding,
+            message: message.to_string(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Transform data between different encodings.
+//!
+//! ```
+//! use trrs::{transcode, Encoding};
+//!
+//! let hex: Encoding = "hex".parse().unwrap();
+//! let base64: Encoding = "base64".parse().unwrap();
+//! assert_eq!(transcode(&hex, &base64, b"68656c6c6f").unwrap(), b"aGVsbG8=");
+//! ```
+
+pub mod codec;
+mod decode;
+pub mod detect;
+mod encode;
+mod encoding;
+mod error;
+pub mod stream;
+
+pub use decode::{decode, decode_with_info};
+pub use encode::encode;
+pub use encoding::Encoding;
+pub use error::{Error, Result};
+pub use stream::{transcode_chain_stream, transcode_stream, Info};
+
+/// Decode `data` from the `input` encoding, and re-encode it as `output`.
+pub fn transcode(input: &Encoding, output: &Encoding, data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    transcode_chain(&[input.clone(), output.clone()], data)
+}
+
+/// Decode `data` from each encoding in `chain` in turn, and then encode it as the last one.
+///
+/// For example, `["hex", "base64", "ascii85"]` turns hex encoded base64 into ascii85. An empty
+/// chain leaves the data as it is.
+pub fn transcode_chain(chain: &[Encoding], data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let mut data = data.as_ref().to_owned();
+    if let Some((output, inputs)) = chain.split_last() {
+        for input in inputs {
+            data = decode(input, data)?;
+        }
+        data = encode(output, data)?;
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{transcode_chain, Encoding};
+
+    #[test]
+    fn chains() {
+        let t = |chain: &[&str], inp: &[u8], out: &[u8]| {
+            let chain = chain
+                .iter()
+                .map(|s| s.parse().unwrap())
+                .collect::<Vec<Encoding>>();
+            assert_eq!(transcode_chain(&chain, inp).unwrap(), out);
+        };
+
+        t(&[], b"hello", b"hello");
+        t(&["hex"], b"hello", b"68656c6c6f");
+        t(&["hex", "raw"], b"68656c6c6f", b"hello");
+        t(&["hex", "base64", "raw"], b"614756736247383d", b"hello");
+        t(
+            &["hex", "base64", "ascii85"],
+            b"614756736247383d",
+            b"<~BOu!rDZ~>",
+        );
+        // A PowerShell -EncodedCommand
+        t(
+            &["base64", "utf16le", "utf8"],
+            b"dwBoAG8AYQBtAGkA",
+            b"whoami",
+        );
+    }
+}
@@ -1,91 +1,128 @@
 mod cli;
-mod decode;
-mod encode;
 
+use std::collections::VecDeque;
 use std::fs::OpenOptions;
-use std::io::{self, Read, Write};
+use std::io::{self, Read};
+use std::path::Path;
 
 use anyhow::{bail, Result};
-use clap::Parser;
+use clap::{CommandFactory, ErrorKind, Parser};
 use cli::{Args, OutputFormat};
 
-// TODO: use https://github.com/skyf0l/BaseCracker as a lib since it supports many encodings
-
 fn main() -> Result<()> {
     //
     // Args
     //
 
     let args = Args::parse();
-    let (input_enc, output_enc) = if args.encoding.is_empty() {
-        (
-            args.input_type.as_ref().unwrap(),
-            args.output_type.as_ref().unwrap(),
-        )
-    } else {
-        (&args.encoding[0], &args.encoding[1])
-    };
+    if args.list {
+        list_encodings();
+        return Ok(());
+    }
+
+    let mut chain = args.chain();
+    if chain.len() < 2 && !args.peel {
+        Args::command()
+            .error(
+                ErrorKind::TooFewValues,
+                "At least two encodings are needed, the input and the output",
+            )
+            .exit();
+    }
 
     if args.output_format.is_some() && args.output != "-" {
         eprintln!("Providing --out-format only makes sense when outputting to STDOUT.");
     }
 
+    let explain = args.explain && chain[0].codec().name() == "auto";
+    if args.explain && !explain {
+        eprintln!("Providing --explain only makes sense when the input type is auto.");
+    }
+
     //
     // Input
     //
 
-    let input = match args.input.as_str() {
-        // Read from STDIN
-        "-" => {
-            let stdin = io::stdin();
-            let mut stdin_data = vec![];
-            if let Err(e) = stdin.lock().read_to_end(&mut stdin_data) {
-                bail!("Failed to read from STDIN: {}", e);
-            }
+    // Encodings that store a file name, like uu, default to the first input's, STDIN doesn't
+    // have one
+    let file_name = match args.input[0].as_str() {
+        "-" => None,
+        path => Path::new(path).file_name().and_then(|name| name.to_str()),
+    };
+    if let (Some(name), Some(output)) = (file_name, chain.last_mut()) {
+        *output = output.with_default_option("name", name)?;
+    }
 
-            stdin_data
-        }
-        // Read from file
-        _ => {
-            let mut data = vec![];
-            match OpenOptions::new().read(true).open(&args.input) {
-                Ok(mut file) => {
-                    if let Err(e) = file.read_to_end(&mut data) {
-                        bail!("Failed to open file: {}", e);
-                    }
-                }
+    // Several inputs are read one after the other
+    let mut inputs = Inputs::default();
+    for path in &args.input {
+        let next: Box<dyn Read> = match path.as_str() {
+            // Read from STDIN
+            "-" => Box::new(io::stdin()),
+            // Read from file
+            _ => match OpenOptions::new().read(true).open(path) {
+                Ok(file) => Box::new(file),
                 Err(e) => bail!("Failed to open file: {}", e),
+            },
+        };
+        inputs.readers.push_back(next);
+    }
+    let mut input: Box<dyn Read> = Box::new(inputs);
+
+    if explain {
+        // Detection needs all of the input anyway
+        let mut data = vec![];
+        if let Err(e) = input.read_to_end(&mut data) {
+            bail!("Failed to read input: {}", e);
+        }
+
+        // Detection is slow, so rather than `auto` doing it again, carry on from the best match
+        match explain_candidates(&data) {
+            Some(decoded) => {
+                chain.remove(0);
+                input = Box::new(io::Cursor::new(decoded));
             }
+            None => input = Box::new(io::Cursor::new(data)),
+        }
+    }
 
-            data
+    if args.peel {
+        let mut data = vec![];
+        if let Err(e) = input.read_to_end(&mut data) {
+            bail!("Failed to read input: {}", e);
         }
-    };
 
-    //
-    // Transform
-    //
+        let (layers, data) = trrs::detect::peel(&data);
+        if layers.is_empty() {
+            eprintln!("Peeled: nothing, the input doesn't look encoded");
+        } else {
+            let layers = layers.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+            eprintln!("Peeled: {}", layers.join(" -> "));
+        }
 
-    let data = decode::decode(input_enc, input)?;
-    let output = encode::encode(output_enc, data)?;
+        input = Box::new(io::Cursor::new(data));
+    }
 
     //
-    // Output
+    // Transform and Output
     //
 
-    match args.output.as_str() {
+    let info = match args.output.as_str() {
         // Print to stdout
         "-" => match args.output_format {
             None | Some(OutputFormat::Raw) => {
-                if let Err(e) = io::stdout().lock().write_all(&output) {
-                    bail!("Failed to write to STDOUT: {}", e)
-                }
+                trrs::transcode_chain_stream(&chain, input, io::stdout())?
             }
             Some(OutputFormat::Safe) => {
+                // bat needs all of the output up front
+                let mut output = vec![];
+                let info = trrs::transcode_chain_stream(&chain, input, &mut output)?;
                 bat::PrettyPrinter::new()
                     .input_from_bytes(&output)
                     .show_nonprintable(true)
                     .print()
                     .expect("Failed to print to STDOUT");
+                info
             }
         },
         // Write to file
@@ -96,25 +133,107 @@ fn main() -> Result<()> {
                 .write(true)
                 .open(&args.output)
             {
-                Ok(mut file) => {
-                    if let Err(e) = file.write_all(&output) {
-                        bail!("Failed to write file: {}", e)
-                    }
-                }
+                Ok(file) => trrs::transcode_chain_stream(&chain, input, file)?,
                 Err(e) => bail!("Failed to create file: {}", e),
             }
         }
+    };
+
+    // Details like version bytes go to STDERR, to keep them out of the output
+    for info in info {
+        eprintln!("{} {}: {}", info.encoding, info.name, info.value);
     }
 
     Ok(())
 }
 
+/// Reads several inputs one after the other, like `Read::chain`, with a line break between two
+/// inputs when the first doesn't end in one, so the lines of text formats like yEnc don't merge.
+#[derive(Default)]
+struct Inputs {
+    readers: VecDeque<Box<dyn Read>>,
+    /// The last byte read from the current input.
+    last: Option<u8>,
+}
+
+impl Read for Inputs {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while let Some(reader) = self.readers.front_mut() {
+            if buf.is_empty() {
+                return Ok(0);
+            }
+
+            let n = reader.read(buf)?;
+            if n > 0 {
+                self.last = Some(buf[n - 1]);
+                return Ok(n);
+            }
+
+            self.readers.pop_front();
+            let last = self.last.take();
+            if !self.readers.is_empty() && last.is_some_and(|b| b != b'\n') {
+                buf[0] = b'\n';
+                return Ok(1);
+            }
+        }
+
+        Ok(0)
+    }
+}
+
+fn list_encodings() {
+    let usage = |name: &str, arg: Option<&str>| match arg {
+        Some(arg) => format!("{}:{}", name, arg),
+        None => name.to_string(),
+    };
+
+    for codec in trrs::codec::registry() {
+        let mut line = format!(
+            "{:<20} {}",
+            usage(codec.name(), codec.argument()),
+            codec.description()
+        );
+        if !codec.aliases().is_empty() {
+            let aliases = codec
+                .aliases()
+                .iter()
+                .map(|alias| usage(alias, codec.argument()))
+                .collect::<Vec<_>>();
+            line.push_str(&format!(" (also {})", aliases.join(", ")));
+        }
+
+        println!("{}", line);
+        for (usage, description) in codec.options() {
+            println!("    ,{:<19} {}", usage, description);
+        }
+    }
+}
+
+/// Print how well each encoding fits `data`, and return it decoded from the best one.
+fn explain_candidates(data: &[u8]) -> Option<Vec<u8>> {
+    eprintln!("score  alphabet  padding  plausibility  encoding");
+    let candidates = trrs::detect::candidates(data);
+    for candidate in &candidates {
+        eprintln!(
+            "{:>5.2}  {:>8.2}  {:>7}  {:>12.2}  {}",
+            candidate.score,
+            candidate.alphabet_fit,
+            if candidate.valid_padding { "yes" } else { "no" },
+            candidate.plausibility,
+            candidate.encoding,
+        );
+    }
+
+    candidates
+        .into_iter()
+        .next()
+        .map(|candidate| candidate.decoded)
+}
+
 #[cfg(test)]
 mod tests {
     use assert_cmd::Command;
 
-    use crate::cli::Encoding;
-
     fn cmd() -> Command {
         Command::cargo_bin(env!("CARGO_PKG_NAME")).unwrap()
     }
@@ -131,6 +250,118 @@ mod tests {
         todo!()
     }
 
+    #[test]
+    fn default_file_name() {
+        cmd()
+            .args(["raw", "uu"])
+            .write_stdin("Cat")
+            .assert()
+            .success()
+            .stdout("begin 644 data\n#0V%T\n`\nend\n");
+
+        let path = std::env::temp_dir().join("trrs-default-file-name.txt");
+        std::fs::write(&path, "Cat").unwrap();
+        cmd()
+            .args(["raw", "uu", "--in"])
+            .arg(&path)
+            .assert()
+            .success()
+            .stdout("begin 644 trrs-default-file-name.txt\n#0V%T\n`\nend\n");
+        std::fs::remove_file(&path).unwrap();
+
+        let header = |cmd: &mut Command| {
+            let out = cmd.assert().success().get_output().stdout.clone();
+            let end = out.iter().position(|b| *b == b'\n').unwrap();
+            String::from_utf8(out[..end].to_vec()).unwrap()
+        };
+        assert_eq!(
+            header(cmd().args(["raw", "yenc"]).write_stdin("Cat")),
+            "=ybegin line=128 size=3 name=data"
+        );
+
+        // With several inputs the first one names the file, unless it's STDIN
+        let (a, b) = (
+            std::env::temp_dir().join("trrs-first.txt"),
+            std::env::temp_dir().join("trrs-second.txt"),
+        );
+        std::fs::write(&a, "C\n").unwrap();
+        std::fs::write(&b, "at\n").unwrap();
+        assert_eq!(
+            header(cmd().args(["raw", "yenc", "-i"]).arg(&a).arg("-i").arg(&b)),
+            "=ybegin line=128 size=5 name=trrs-first.txt"
+        );
+        assert_eq!(
+            header(
+                cmd()
+                    .args(["raw", "yenc", "-i", "-", "-i"])
+                    .arg(&a)
+                    .write_stdin("at\n")
+            ),
+            "=ybegin line=128 size=5 name=data"
+        );
+        std::fs::remove_file(&a).unwrap();
+        std::fs::remove_file(&b).unwrap();
+    }
+
+    #[test]
+    fn explain() {
+        let out = cmd()
+            .args(["auto", "ascii", "--explain"])
+            .write_stdin("aGVsbG8gd29ybGQ=\n")
+            .assert()
+            .success()
+            .stdout("hello world");
+        let stderr = String::from_utf8_lossy(&out.get_output().stderr).into_owned();
+        assert!(
+            stderr.lines().nth(1).unwrap().ends_with("base64"),
+            "{}",
+            stderr
+        );
+    }
+
+    #[test]
+    fn multiple_inputs() {
+        // The first part doesn't end in a line break, which mustn't join it to the second
+        let (a, b) = (
+            std::env::temp_dir().join("trrs-part1.yenc"),
+            std::env::temp_dir().join("trrs-part2.yenc"),
+        );
+        std::fs::write(
+            &a,
+            b"=ybegin part=1 total=2 line=128 size=11 name=hi.txt\n=ypart begin=1 end=5\n\x92\x8f\x96\x96\x99\n=yend size=5 part=1 pcrc32=3610a686",
+        )
+        .unwrap();
+        std::fs::write(
+            &b,
+            b"=ybegin part=2 total=2 line=128 size=11 name=hi.txt\n=ypart begin=6 end=11\nJ\xa1\x99\x9c\x96\x8e\n=yend size=6 part=2 pcrc32=4a3b42cb crc32=0d4a1185\n",
+        )
+        .unwrap();
+        cmd()
+            .args(["yenc", "raw", "-i"])
+            .arg(&a)
+            .arg("-i")
+            .arg(&b)
+            .assert()
+            .success()
+            .stdout("hello world");
+
+        // Only a missing line break is added
+        for first in ["C", "C\n"] {
+            std::fs::write(&a, first).unwrap();
+            std::fs::write(&b, "at").unwrap();
+            cmd()
+                .args(["raw", "raw", "-i"])
+                .arg(&a)
+                .arg("-i")
+                .arg(&b)
+                .assert()
+                .success()
+                .stdout("C\nat");
+        }
+        std::fs::remove_file(&a).unwrap();
+        std::fs::remove_file(&b).unwrap();
+    }
+
     #[test]
     fn stdin_and_stdout() {
         let s = {
@@ -144,64 +375,64 @@ mod tests {
         let v = s.as_bytes().to_vec();
 
         let data = vec![
-            (Encoding::Raw, v.clone()),
-            (Encoding::ASCII, v.clone()),
-            (Encoding::UTF8, v.clone()),
-            (Encoding::Hex, hex::encode(v.clone()).into_bytes()),
+            ("raw", v.clone()),
+            ("ascii", v.clone()),
+            ("utf8", v.clone()),
+            ("hex", hex::encode(v.clone()).into_bytes()),
             (
-                Encoding::Base32Crockford,
+                "base32:crockford",
                 base32::encode(base32::Alphabet::Crockford, &v.clone()).into_bytes(),
             ),
             (
-                Encoding::Base32Rfc4648,
+                "base32",
                 base32::encode(base32::Alphabet::RFC4648 { padding: true }, &v.clone())
                     .into_bytes(),
             ),
             (
-                Encoding::Base32Rfc4648NoPadding,
+                "base32|",
                 base32::encode(base32::Alphabet::RFC4648 { padding: false }, &v.clone())
                     .into_bytes(),
             ),
             (
-                Encoding::Base64Bcrypt,
+                "base64:bcrypt",
                 base64::encode_config(v.clone(), base64::BCRYPT).into_bytes(),
             ),
             (
-                Encoding::Base64Binhex,
+                "base64:binhex",
                 base64::encode_config(v.clone(), base64::BINHEX).into_bytes(),
             ),
             (
-                Encoding::Base64Crypt,
+                "base64:crypt",
                 base64::encode_config(v.clone(), base64::CRYPT).into_bytes(),
             ),
             (
-                Encoding::Base64ImapMutf7,
+                "base64:imap",
                 base64::encode_config(v.clone(), base64::IMAP_MUTF7).into_bytes(),
             ),
             (
-                Encoding::Base64Standard,
+                "base64",
                 base64::encode_config(v.clone(), base64::STANDARD).into_bytes(),
             ),
             (
-                Encoding::Base64StandardNoPadding,
+                "base64|",
                 base64::encode_config(v.clone(), base64::STANDARD_NO_PAD).into_bytes(),
             ),
             (
-                Encoding::Base64UrlSafe,
+                "base64:url",
                 base64::encode_config(v.clone(), base64::URL_SAFE).into_bytes(),
             ),
             (
-                Encoding::Base64UrlSafeNoPadding,
+                "base64:url|",
                 base64::encode_config(v.clone(), base64::URL_SAFE_NO_PAD).into_bytes(),
             ),
-            (Encoding::Base85Rfc1924, base85::encode(&v).into_bytes()),
-            (Encoding::Base85Ascii, ascii85::encode(&v).into_bytes()),
+            ("base85", base85::encode(&v).into_bytes()),
+            ("ascii85", ascii85::encode(&v).into_bytes()),
         ];
 
         for (in_enc, in_data) in &data {
             for (out_enc, out_data) in &data {
                 cmd()
-                    .args(&["-I", &in_enc.to_string(), "-O", &out_enc.to_string()])
+                    .args(["-I", in_enc, "-O", out_enc])
                     .write_stdin(in_data.to_owned())
                     .assert()
                     .success()
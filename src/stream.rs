@@ -0,0 +1,322 @@
+use std::io::{ErrorKind, Read, Write};
+use std::mem;
+
+use crate::{Encoding, Result};
+
+/// How much input is read at a time when transcoding a stream.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// An incremental encoder or decoder, which is fed its input a chunk at a time.
+///
+/// Output is produced as soon as possible, so when some input turns out to be invalid, the output
+/// for the input before it may already have been passed on.
+pub trait Transform {
+    /// Process the next chunk of input, appending any output to `out`.
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()>;
+
+    /// Process whatever input is left once there is no more to come.
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()>;
+
+    /// Details found in the input once it's finished, like the version byte of base58check, as
+    /// pairs of names and values.
+    fn info(&self) -> Vec<(&'static str, String)> {
+        vec![]
+    }
+}
+
+/// A detail found in the input while decoding it, see [`Transform::info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    /// The name of the encoding it was found in.
+    pub encoding: String,
+    pub name: &'static str,
+    pub value: String,
+}
+
+/// Holds on to all of its input, and transforms it in one go when finished.
+///
+/// This is used for encodings that can't be processed incrementally.
+pub(crate) struct Buffered<F> {
+    f: F,
+    buf: Vec<u8>,
+}
+
+impl<F> Buffered<F> {
+    pub fn new(f: F) -> Self {
+        Self { f, buf: vec![] }
+    }
+}
+
+impl<F: FnMut(&[u8]) -> Result<Vec<u8>>> Transform for Buffered<F> {
+    fn update(&mut self, input: &[u8], _: &mut Vec<u8>) -> Result<()> {
+        self.buf.extend_from_slice(input);
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        out.extend((self.f)(&mem::take(&mut self.buf))?);
+        Ok(())
+    }
+}
+
+/// Like `Buffered`, for transformations that also find details in their input, see
+/// `Transform::info`.
+pub(crate) struct BufferedWithInfo<F> {
+    f: F,
+    buf: Vec<u8>,
+    info: Vec<(&'static str, String)>,
+}
+
+impl<F> BufferedWithInfo<F> {
+    pub fn new(f: F) -> Self {
+        Self {
+            f,
+            buf: vec![],
+            info: vec![],
+        }
+    }
+}
+
+type WithInfo = (Vec<u8>, Vec<(&'static str, String)>);
+
+impl<F: FnMut(&[u8]) -> Result<WithInfo>> Transform for BufferedWithInfo<F> {
+    fn update(&mut self, input: &[u8], _: &mut Vec<u8>) -> Result<()> {
+        self.buf.extend_from_slice(input);
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        let (data, info) = (self.f)(&mem::take(&mut self.buf))?;
+        out.extend(data);
+        self.info = info;
+        Ok(())
+    }
+
+    fn info(&self) -> Vec<(&'static str, String)> {
+        self.info.clone()
+    }
+}
+
+/// Transforms its input in blocks of a fixed size, so at most one partial block is held in memory.
+///
+/// `f` is also given the offset of the blocks in the whole input, for its error messages. Any
+/// trailing partial block is transformed when finished.
+pub(crate) struct Blocks<F> {
+    size: usize,
+    f: F,
+    buf: Vec<u8>,
+    offset: usize,
+}
+
+impl<F> Blocks<F> {
+    pub fn new(size: usize, f: F) -> Self {
+        Self {
+            size,
+            f,
+            buf: vec![],
+            offset: 0,
+        }
+    }
+}
+
+impl<F: FnMut(&[u8], usize) -> Result<Vec<u8>>> Transform for Blocks<F> {
+    fn update(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        self.buf.extend_from_slice(input);
+        let n = self.buf.len() - self.buf.len() % self.size;
+        if n > 0 {
+            out.extend((self.f)(&self.buf[..n], self.offset)?);
+            self.buf.drain(..n);
+            self.offset += n;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self, out: &mut Vec<u8>) -> Result<()> {
+        if !self.buf.is_empty() {
+            out.extend((self.f)(&mem::take(&mut self.buf), self.offset)?);
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode everything read from `reader` as `input`, and write it to `writer` encoded as `output`.
+///
+/// Returns the details found in the input while decoding it.
+///
+/// Unlike [`transcode`](crate::transcode), the data is processed in chunks, so encodings which
+/// support it can be transcoded without holding all of the data in memory. This also means that
+/// if the input is invalid, whatever was transcoded before the error was found has already been
+/// written to `writer`.
+pub fn transcode_stream(
+    input: &Encoding,
+    output: &Encoding,
+    reader: impl Read,
+    writer: impl Write,
+) -> Result<Vec<Info>> {
+    transcode_chain_stream(&[input.clone(), output.clone()], reader, writer)
+}
+
+/// The streaming version of [`transcode_chain`](crate::transcode_chain).
+pub fn transcode_chain_stream(
+    chain: &[Encoding],
+    mut reader: impl Read,
+    mut writer: impl Write,
+) -> Result<Vec<Info>> {
+    let mut stages = match chain.split_last() {
+        Some((output, inputs)) => inputs
+            .iter()
+            .map(|enc| enc.codec().decoder())
+            .chain([output.codec().encoder()])
+            .collect(),
+        None => vec![],
+    };
+
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let n = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        writer.write_all(&run(&mut stages, &chunk[..n], false)?)?;
+    }
+
+    writer.write_all(&run(&mut stages, &[], true)?)?;
+    writer.flush()?;
+
+    Ok(info(chain, &stages))
+}
+
+/// Pass `input` through each stage of a chain, finishing each stage if there's no more input.
+pub(crate) fn run(
+    stages: &mut [Box<dyn Transform + '_>],
+    input: &[u8],
+    finish: bool,
+) -> Result<Vec<u8>> {
+    let mut data = input.to_owned();
+    for stage in stages {
+        let mut out = vec![];
+        stage.update(&data, &mut out)?;
+        if finish {
+            stage.finish(&mut out)?;
+        }
+        data = out;
+    }
+
+    Ok(data)
+}
+
+/// Collect the details each stage of a chain found, see `Transform::info`.
+pub(crate) fn info(chain: &[Encoding], stages: &[Box<dyn Transform + '_>]) -> Vec<Info> {
+    chain
+        .iter()
+        .zip(stages)
+        .flat_map(|(encoding, stage)| {
+            stage.info().into_iter().map(|(name, value)| Info {
+                encoding: encoding.to_string(),
+                name,
+                value,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codec::registry;
+    use crate::stream::{transcode_chain_stream, transcode_stream, Transform, CHUNK_SIZE};
+    use crate::{decode, encode, transcode_chain, Encoding};
+
+    fn chunked(mut t: Box<dyn Transform + '_>, data: &[u8], size: usize) -> Vec<u8> {
+        let mut out = vec![];
+        for chunk in data.chunks(size) {
+            t.update(chunk, &mut out).unwrap();
+        }
+        t.finish(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn matches_whole_input() {
+        // Leading zeros exercise ascii85's `z` shorthand
+        let data = [0; 8]
+            .into_iter()
+            .chain((0..=0x7f).cycle().take(1000))
+            .collect::<Vec<u8>>();
+        let without_nul = data
+            .iter()
+            .copied()
+            .filter(|b| *b != 0)
+            .collect::<Vec<u8>>();
+        for codec in registry() {
+            let mut enc = Encoding::from(*codec);
+            let mut data = &data[..];
+            match codec.name() {
+                // This can only be used for input
+                "auto" => continue,
+                "bech32" | "bech32m" => enc = enc.with_default_option("hrp", "bc").unwrap(),
+                "literal:shell" => data = &without_nul,
+                _ => {}
+            }
+
+            let encoded = encode(&enc, data).unwrap_or_else(|e| panic!("{}: {}", enc, e));
+            for size in [1, 2, 3, 7, 64, 1000] {
+                assert_eq!(
+                    chunked(enc.codec().encoder(), data, size),
+                    encoded,
+                    "{}",
+                    enc
+                );
+                assert_eq!(
+                    chunked(enc.codec().decoder(), &encoded, size),
+                    decode(&enc, &encoded).unwrap(),
+                    "{}",
+                    enc
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reports_offsets_in_whole_input() {
+        let t = |enc: &str, bad: &str, message: &str| {
+            let offset = CHUNK_SIZE + 1000;
+            let data = [&b"0".repeat(offset)[..], bad.as_bytes()].concat();
+            let e = transcode_stream(
+                &enc.parse().unwrap(),
+                &"raw".parse().unwrap(),
+                &data[..],
+                vec![],
+            )
+            .unwrap_err();
+            let message = format!("{} {}", message, offset);
+            assert!(e.to_string().contains(&message), "{}: {}", enc, e);
+        };
+
+        t("hex", "zz", "Invalid character 'z' at position");
+        t("base64", "!AAA", "Invalid byte 33, offset");
+        t(
+            "base32:geohash",
+            "a0000000",
+            "invalid character 'a' at offset",
+        );
+        t("z85", "~0000", "invalid character '~' at offset");
+    }
+
+    #[test]
+    fn chains() {
+        let chain = ["hex", "base64", "ascii85"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect::<Vec<Encoding>>();
+        let data = encode(&chain[0], encode(&chain[1], [0xff; 1000]).unwrap()).unwrap();
+
+        let mut out = vec![];
+        transcode_chain_stream(&chain, &data[..], &mut out).unwrap();
+        assert_eq!(out, transcode_chain(&chain, &data).unwrap());
+    }
+}